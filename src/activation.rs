@@ -0,0 +1,139 @@
+pub mod activation {
+  use crate::functions::{sigmoid, sigmoid_derivative};
+  use crate::matrix::{hadamard, Element, Mat, MatError};
+
+  // sqrt(2 / pi) and the cubic coefficient of the tanh approximation of GELU
+  const GELU_SCALE: f64 = 0.7978845608028654;
+  const GELU_CUBIC: f64 = 0.044715;
+
+  // Nonlinearity applied to the weighted input `z` of a layer. Every variant except `Softmax`
+  // works element by element; `Softmax` normalizes each row into a probability distribution.
+  #[derive(Clone, Copy, Debug, PartialEq)]
+  pub enum Activation {
+    Sigmoid,
+    Tanh,
+    Relu,
+    // Slope used for negative inputs
+    LeakyRelu(f64),
+    // Saturation value `alpha` for negative inputs: alpha * (e^x - 1)
+    Elu(f64),
+    // Tanh approximation of the Gaussian error linear unit
+    Gelu,
+    Softplus,
+    Identity,
+    Softmax,
+  }
+
+  impl Activation {
+    // Value of an elementwise activation at `x`. `Softmax` has no value for a single element,
+    // rows go through `Mat::apply` instead.
+    pub fn forward<T: Element>(&self, x: T) -> Result<T, MatError> {
+      return match *self {
+        Activation::Softmax => Err(MatError::NotElementwise {
+          operation: "Activation",
+        }),
+        _ => Ok(self.value(x)),
+      };
+    }
+
+    // Derivative of an elementwise activation with respect to the input `x`. For `Softmax`
+    // every output depends on the whole row, use `backward` instead.
+    pub fn derivative<T: Element>(&self, x: T) -> Result<T, MatError> {
+      return match *self {
+        Activation::Softmax => Err(MatError::NotElementwise {
+          operation: "Activation derivative",
+        }),
+        _ => Ok(self.slope(x)),
+      };
+    }
+
+    // `forward` for the variants that work element by element
+    pub(crate) fn value<T: Element>(&self, x: T) -> T {
+      let (zero, one, half) = (T::zero(), T::one(), T::constant(0.5));
+      return match *self {
+        Activation::Sigmoid => sigmoid(x),
+        Activation::Tanh => x.tanh(),
+        Activation::Relu => x.max(zero),
+        Activation::LeakyRelu(alpha) => {
+          if x > zero {
+            x
+          } else {
+            T::constant(alpha) * x
+          }
+        }
+        Activation::Elu(alpha) => {
+          if x > zero {
+            x
+          } else {
+            T::constant(alpha) * x.exp_m1()
+          }
+        }
+        Activation::Gelu => {
+          let (scale, cubic) = (T::constant(GELU_SCALE), T::constant(GELU_CUBIC));
+          half * x * (one + (scale * (x + cubic * x * x * x)).tanh())
+        }
+        // Written so that large |x| neither overflows nor loses precision
+        Activation::Softplus => x.max(zero) + (-x.abs()).exp().ln_1p(),
+        Activation::Identity => x,
+        Activation::Softmax => unreachable!("Softmax is applied row by row"),
+      };
+    }
+
+    // `derivative` for the variants that work element by element
+    fn slope<T: Element>(&self, x: T) -> T {
+      let (zero, one, half) = (T::zero(), T::one(), T::constant(0.5));
+      return match *self {
+        Activation::Sigmoid => sigmoid_derivative(sigmoid(x)),
+        Activation::Tanh => one - x.tanh().powi(2),
+        Activation::Relu => {
+          if x > zero {
+            one
+          } else {
+            zero
+          }
+        }
+        Activation::LeakyRelu(alpha) => {
+          if x > zero {
+            one
+          } else {
+            T::constant(alpha)
+          }
+        }
+        Activation::Elu(alpha) => {
+          if x > zero {
+            one
+          } else {
+            T::constant(alpha) * x.exp()
+          }
+        }
+        Activation::Gelu => {
+          let (scale, cubic) = (T::constant(GELU_SCALE), T::constant(GELU_CUBIC));
+          let t = (scale * (x + cubic * x * x * x)).tanh();
+          let three = T::constant(3.0);
+          half * (one + t) + half * x * (one - t * t) * scale * (one + three * cubic * x * x)
+        }
+        Activation::Softplus => sigmoid(x),
+        Activation::Identity => one,
+        Activation::Softmax => unreachable!("Softmax is differentiated row by row"),
+      };
+    }
+
+    // Chain rule through this activation for one 1 x n row: turns the gradient `da` with
+    // respect to the outputs `a` into the gradient with respect to the inputs `z`.
+    pub fn backward<T: Element>(&self, z: &Mat<T>, a: &Mat<T>, da: &Mat<T>) -> Mat<T> {
+      if *self == Activation::Softmax {
+        let mut dz = Mat::zeros(1, da.cols());
+        // The Jacobian of softmax is diag(a) - a a^T
+        let mut weighted = T::zero();
+        for j in 0..da.cols() {
+          weighted += da[(0, j)] * a[(0, j)];
+        }
+        for j in 0..da.cols() {
+          dz[(0, j)] = a[(0, j)] * (da[(0, j)] - weighted);
+        }
+        return dz;
+      }
+      return hadamard(da, &z.map(|x| self.slope(x)));
+    }
+  }
+}
@@ -0,0 +1,40 @@
+// Throughput of `dot_product` for square and skinny shapes. Run with `cargo bench`.
+use nn::matrix::{dot_product, Mat};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// Runs `a * b` until at least half a second has passed and returns the best GFLOP/s seen
+fn measure(a: &Mat, b: &Mat) -> f64 {
+  let flops = 2.0 * a.rows() as f64 * a.cols() as f64 * b.cols() as f64;
+  let mut best = f64::INFINITY;
+  let started = Instant::now();
+  while started.elapsed() < Duration::from_millis(500) {
+    let run = Instant::now();
+    black_box(dot_product(black_box(a), black_box(b)));
+    best = best.min(run.elapsed().as_secs_f64());
+  }
+  return flops / best / 1e9;
+}
+
+fn main() {
+  let shapes = [
+    ("square", 64, 64, 64),
+    ("square", 128, 128, 128),
+    ("square", 256, 256, 256),
+    ("square", 512, 512, 512),
+    ("skinny", 4096, 32, 32),
+    ("skinny", 32, 4096, 32),
+    ("skinny", 1, 512, 512),
+    ("skinny", 512, 512, 1),
+  ];
+
+  println!("{:<8} {:>18} {:>10}", "shape", "m x k x n", "GFLOP/s");
+  for (name, m, k, n) in shapes {
+    let mut a = Mat::new(m, k);
+    let mut b = Mat::new(k, n);
+    a.rand(-1.0, 1.0);
+    b.rand(-1.0, 1.0);
+    let dims = format!("{} x {} x {}", m, k, n);
+    println!("{:<8} {:>18} {:>10.2}", name, dims, measure(&a, &b));
+  }
+}
@@ -0,0 +1,154 @@
+pub mod loss {
+  use crate::matrix::{Element, Mat, MatView};
+
+  // Keeps logarithms and divisions finite when an output saturates at 0 or 1. Types too coarse
+  // to tell 1 - 1e-12 from 1 use their machine epsilon instead.
+  const PROBABILITY_EPSILON: f64 = 1e-12;
+
+  // A loss scores a single sample: `output` and `target` are both 1 x n rows.
+  pub trait Loss<T: Element = f64> {
+    fn value(&self, output: MatView<T>, target: MatView<T>) -> T;
+    // Gradient of `value` with respect to every entry of `output`, as a 1 x n row
+    fn derivative(&self, output: MatView<T>, target: MatView<T>) -> Mat<T>;
+  }
+
+  // Mean squared error over the outputs of a sample
+  pub struct Mse;
+
+  // Binary cross-entropy, for outputs that are independent probabilities (e.g. sigmoid)
+  pub struct BinaryCrossEntropy;
+
+  // Categorical cross-entropy, for outputs that form a probability distribution. Pair it with
+  // a softmax output layer and one-hot targets.
+  pub struct CategoricalCrossEntropy;
+
+  // Quadratic for errors up to `delta` and linear beyond, so outliers pull less than with `Mse`
+  pub struct Huber {
+    pub delta: f64,
+  }
+
+  impl Huber {
+    pub fn new(delta: f64) -> Huber {
+      assert!(
+        delta > 0.0,
+        "Huber delta must be greater than 0. Got {}",
+        delta
+      );
+      return Huber { delta };
+    }
+  }
+
+  fn assert_same_shape<T>(output: MatView<T>, target: MatView<T>) {
+    assert!(
+      output.rows() == target.rows() && output.cols() == target.cols(),
+      "Output and target dimensions must match. Got output: ({}x{}) and target: ({}x{})",
+      output.rows(),
+      output.cols(),
+      target.rows(),
+      target.cols()
+    );
+  }
+
+  fn clamp_probability<T: Element>(value: T) -> T {
+    let epsilon = T::constant(PROBABILITY_EPSILON).max(T::epsilon());
+    return value.max(epsilon).min(T::one() - epsilon);
+  }
+
+  impl<T: Element> Loss<T> for Mse {
+    fn value(&self, output: MatView<T>, target: MatView<T>) -> T {
+      assert_same_shape(output, target);
+      let mut sum = T::zero();
+      for j in 0..output.cols() {
+        let diff = output[(0, j)] - target[(0, j)];
+        sum += diff * diff;
+      }
+      return sum / T::constant(output.cols() as f64);
+    }
+
+    fn derivative(&self, output: MatView<T>, target: MatView<T>) -> Mat<T> {
+      assert_same_shape(output, target);
+      let n = T::constant(output.cols() as f64);
+      let mut result = Mat::zeros(1, output.cols());
+      for j in 0..output.cols() {
+        let diff = output[(0, j)] - target[(0, j)];
+        result[(0, j)] = T::constant(2.0) * diff / n;
+      }
+      return result;
+    }
+  }
+
+  impl<T: Element> Loss<T> for BinaryCrossEntropy {
+    fn value(&self, output: MatView<T>, target: MatView<T>) -> T {
+      assert_same_shape(output, target);
+      let mut sum = T::zero();
+      for j in 0..output.cols() {
+        let o = clamp_probability(output[(0, j)]);
+        let t = target[(0, j)];
+        sum -= t * o.ln() + (T::one() - t) * (T::one() - o).ln();
+      }
+      return sum / T::constant(output.cols() as f64);
+    }
+
+    fn derivative(&self, output: MatView<T>, target: MatView<T>) -> Mat<T> {
+      assert_same_shape(output, target);
+      let n = T::constant(output.cols() as f64);
+      let mut result = Mat::zeros(1, output.cols());
+      for j in 0..output.cols() {
+        let o = clamp_probability(output[(0, j)]);
+        let t = target[(0, j)];
+        result[(0, j)] = (o - t) / (o * (T::one() - o)) / n;
+      }
+      return result;
+    }
+  }
+
+  impl<T: Element> Loss<T> for CategoricalCrossEntropy {
+    fn value(&self, output: MatView<T>, target: MatView<T>) -> T {
+      assert_same_shape(output, target);
+      let mut sum = T::zero();
+      for j in 0..output.cols() {
+        let o = clamp_probability(output[(0, j)]);
+        sum -= target[(0, j)] * o.ln();
+      }
+      return sum;
+    }
+
+    fn derivative(&self, output: MatView<T>, target: MatView<T>) -> Mat<T> {
+      assert_same_shape(output, target);
+      let mut result = Mat::zeros(1, output.cols());
+      for j in 0..output.cols() {
+        let o = clamp_probability(output[(0, j)]);
+        result[(0, j)] = -target[(0, j)] / o;
+      }
+      return result;
+    }
+  }
+
+  impl<T: Element> Loss<T> for Huber {
+    fn value(&self, output: MatView<T>, target: MatView<T>) -> T {
+      assert_same_shape(output, target);
+      let (delta, half) = (T::constant(self.delta), T::constant(0.5));
+      let mut sum = T::zero();
+      for j in 0..output.cols() {
+        let diff = (output[(0, j)] - target[(0, j)]).abs();
+        if diff <= delta {
+          sum += half * diff * diff;
+        } else {
+          sum += delta * (diff - half * delta);
+        }
+      }
+      return sum / T::constant(output.cols() as f64);
+    }
+
+    fn derivative(&self, output: MatView<T>, target: MatView<T>) -> Mat<T> {
+      assert_same_shape(output, target);
+      let (n, delta) = (T::constant(output.cols() as f64), T::constant(self.delta));
+      let mut result = Mat::zeros(1, output.cols());
+      for j in 0..output.cols() {
+        let diff = output[(0, j)] - target[(0, j)];
+        result[(0, j)] = diff.max(-delta).min(delta) / n;
+      }
+      return result;
+    }
+  }
+}
@@ -1,53 +1,56 @@
-mod network; // Include the network module
+use nn::loss::loss::Mse;
 use nn::matrix::*;
-use std::mem;
+use nn::network::network::Network;
 
-use crate::network::network::Network;
 fn main() {
   let stride = 3;
-  let mut training_data: Vec<f64> =
-    vec![0.0, 0.0, 0.0,
-     0.0, 1.0, 1.0,
-      1.0, 0.0, 1.0,
-       1.0, 1.0, 0.0];
-       println!("data: {}", training_data.len());
+  let training_data: Vec<f64> = vec![
+    0.0, 0.0, 0.0, //
+    0.0, 1.0, 1.0, //
+    1.0, 0.0, 1.0, //
+    1.0, 1.0, 0.0,
+  ];
+  println!("data: {}", training_data.len());
   let n = size_of_element(&training_data, stride);
   println!("n: {}", n);
-  let training_inputs = Mat {
-    rows: n,
-    cols: 2,
-    stride: stride,
-    data_stream: training_data.as_mut_ptr(),
-  };
-  let training_outputs = Mat {
-    rows: n,
-    cols: 1,
-    stride: stride,
-    data_stream: unsafe { training_data.as_mut_ptr().add(2) },
-  };
-  // training_inputs.print(None, None);
-  // training_outputs.print(None, None);
-  // let training_inputs = Mat::new()
+  // Both views share `training_data`: the first two columns are the inputs, the last the output
+  let training_inputs = MatView::new(&training_data, n, 2, stride);
+  let training_outputs = MatView::new(&training_data[2..], n, 1, stride);
+  training_inputs
+    .to_mat()
+    .print("training_inputs", None, None);
+  training_outputs
+    .to_mat()
+    .print("training_outputs", None, None);
+
   let mut network = Network::new(&[2, 2, 1]);
+  network.rand(0.0, 1.0);
+
+  let loss = Mse;
+  let learning_rate = 1.0;
+  println!(
+    "cost: {}",
+    network.cost(&training_inputs, &training_outputs, &loss)
+  );
+  for epoch in 0..20_000 {
+    let grads = network.backprop(&training_inputs, &training_outputs, &loss);
+    network.apply_gradients(&grads, learning_rate);
+    if epoch % 2_000 == 0 {
+      let cost = network.cost(&training_inputs, &training_outputs, &loss);
+      println!("epoch {}: cost = {}", epoch, cost);
+    }
+  }
   network.print(Some(5), Some(10));
-  let mut network_activations_vec = network.get_activations();
-  let mut network_activations = network_activations_vec.as_mut_slice();
-  training_inputs.print("training_inputs", None, None);
-  let training_row = mat_row(&training_inputs, 0);
-  network_activations[0].print("activations", None, None);
-  training_row.print("training_row", None, None);
-  mat_copy(&mut network_activations[0], &training_row);
-  network_activations[0].print("activations", None, None);
-  return;
-  let mat1 = Mat::new(2, 2);
-  let mat2 = Mat::new(2, 2);
-  let mat = addition(&mat1, &mat2);
-  network.print(Some(5), Some(10));
-  network.print(Some(5), Some(10));
-  network.print(Some(5), Some(10));
-  network.print(Some(5), Some(10));
-  // network.forward();
-  // network.print(None, Some(30))
+
+  let outputs = network.predict(&training_inputs);
+  for i in 0..n {
+    println!(
+      "{} ^ {} = {:.4}",
+      training_inputs[(i, 0)],
+      training_inputs[(i, 1)],
+      outputs[(i, 0)]
+    );
+  }
 }
 
 fn size_of_element<T>(vector: &[T], stride: usize) -> usize {
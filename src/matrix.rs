@@ -1,49 +1,619 @@
+pub mod activation;
 #[path = "utils/functions.rs"]
 mod functions;
+pub mod loss;
 #[path = "utils/macros.rs"]
 mod macros;
+pub mod network;
+pub mod optimizer;
+pub mod trainer;
 pub mod matrix {
+  mod element;
+  mod elementwise;
+  mod kernels;
+  mod linalg;
+  mod ops;
+  mod reductions;
+  pub mod simd;
+  mod slicing;
+  mod spectral;
+
+  pub use element::Element;
+  pub use elementwise::{add_scalar, scale};
+  pub use linalg::{
+    cholesky, det, inverse, lstsq, lu, qr, solve, try_cholesky, try_det, try_inverse, try_lstsq,
+    try_lu, try_solve, Lu, Qr,
+  };
+  pub use reductions::{Axis, Norm};
+  pub use slicing::TransposedView;
+  pub use spectral::{svd, symmetric_eigen, try_symmetric_eigen, Pca, Svd, SymmetricEigen};
+
   use crate::*;
   use num_traits::NumCast;
   use rand::{thread_rng, Rng};
 
-  use std::ops::Sub;
+  use std::fmt;
+
+  use crate::activation::activation::Activation;
+  // An owned, row-major matrix. Cloning copies the buffer. Elements are f64 unless another
+  // `Element` type is named, e.g. `Mat<f32>` for half the memory.
+  #[derive(Clone, Debug, PartialEq)]
+  pub struct Mat<T = f64> {
+    rows: usize,
+    cols: usize,
+    stride: usize, // How far do want to jump in case we want to split the matrixes
+    data_stream: Vec<T>,
+  }
+
+  // Read-only window into a buffer owned by someone else. Row `i` starts at `i * stride`, so a
+  // view can pick some columns out of a wider buffer, e.g. the inputs of a training table.
+  #[derive(Clone, Copy, Debug)]
+  pub struct MatView<'a, T = f64> {
+    pub rows: usize,
+    pub cols: usize,
+    pub stride: usize,
+    data_stream: &'a [T],
+  }
 
-  use super::functions::sigmoid;
-  #[derive(Clone)]
-  pub struct Mat {
+  // Mutable counterpart of `MatView`
+  #[derive(Debug)]
+  pub struct MatViewMut<'a, T = f64> {
     pub rows: usize,
     pub cols: usize,
-    pub stride: usize, // How far do want to jump in case we want to split the matrixes
-    pub data_stream: *mut f64,
+    pub stride: usize,
+    data_stream: &'a mut [T],
+  }
+
+  // Anything that can be read as a matrix: `Mat`, `MatView` and `MatViewMut`
+  pub trait AsView<T = f64> {
+    fn view(&self) -> MatView<'_, T>;
+  }
+
+  // Anything that can be written as a matrix: `Mat` and `MatViewMut`
+  pub trait AsViewMut<T = f64> {
+    fn view_mut(&mut self) -> MatViewMut<'_, T>;
+  }
+
+  // Everything that can go wrong in a matrix operation. The `try_*` functions return it, their
+  // panicking counterparts panic with its message.
+  #[derive(Clone, Debug, PartialEq)]
+  pub enum MatError {
+    // The operands of `operation` have shapes that do not fit together
+    ShapeMismatch {
+      operation: &'static str,
+      left: (usize, usize),
+      right: (usize, usize),
+    },
+    OutOfBounds {
+      operation: &'static str,
+      row: usize,
+      col: usize,
+      rows: usize,
+      cols: usize,
+    },
+    ZeroDimension {
+      rows: usize,
+      cols: usize,
+    },
+    // rows * cols, or the extent of a view, does not fit into usize
+    Overflow,
+    // A `rows x cols` matrix was built from a buffer with `len` elements
+    LengthMismatch {
+      rows: usize,
+      cols: usize,
+      len: usize,
+    },
+    // A view with `stride` does not fit into a buffer of `len` elements
+    BufferTooSmall {
+      rows: usize,
+      cols: usize,
+      stride: usize,
+      needed: usize,
+      len: usize,
+    },
+    InvalidStride {
+      stride: usize,
+      cols: usize,
+    },
+    // `operation` only works on square matrices
+    NotSquare {
+      operation: &'static str,
+      rows: usize,
+      cols: usize,
+    },
+    // The matrix has no inverse, so `operation` has no unique result
+    Singular {
+      operation: &'static str,
+    },
+    // `operation` needs a symmetric matrix
+    NotSymmetric {
+      operation: &'static str,
+    },
+    NotPositiveDefinite {
+      operation: &'static str,
+    },
+    // PCA can keep between 1 and `available` components, but was asked for `requested`
+    InvalidComponentCount {
+      requested: usize,
+      available: usize,
+    },
+    // `operation` looked at a single element of `Activation::Softmax`, which only works on rows
+    NotElementwise {
+      operation: &'static str,
+    },
+    // Reshaping needs the rows of a view to follow each other without gaps
+    NotContiguous {
+      rows: usize,
+      cols: usize,
+      stride: usize,
+    },
+  }
+
+  impl fmt::Display for MatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      return match *self {
+        MatError::ShapeMismatch {
+          operation,
+          left,
+          right,
+        } => write!(
+          f,
+          "{} failed! Matrix dimensions do not fit. Got Mat1: ({}x{}) and Mat2: ({}x{})",
+          operation, left.0, left.1, right.0, right.1
+        ),
+        MatError::OutOfBounds {
+          operation,
+          row,
+          col,
+          rows,
+          cols,
+        } => {
+          if row >= rows {
+            write!(
+              f,
+              "{} failed! Supplied row Index {} is out of bounds. Matrix has {} rows.",
+              operation, row, rows
+            )
+          } else {
+            write!(
+              f,
+              "{} failed! Supplied column Index {} is out of bounds. Matrix has {} columns.",
+              operation, col, cols
+            )
+          }
+        }
+        MatError::ZeroDimension { rows, .. } => {
+          if rows == 0 {
+            write!(f, "Number of rows must be greater than 0.")
+          } else {
+            write!(f, "Number of columns must be greater than 0.")
+          }
+        }
+        MatError::Overflow => write!(f, "Multiplication overflow"),
+        MatError::LengthMismatch { rows, cols, len } => write!(
+          f,
+          "A ({}x{}) matrix needs {} elements. Got {}.",
+          rows,
+          cols,
+          rows.saturating_mul(cols),
+          len
+        ),
+        MatError::BufferTooSmall {
+          rows,
+          cols,
+          stride,
+          needed,
+          len,
+        } => write!(
+          f,
+          "A ({}x{}) view with stride {} needs {} elements, but the buffer only has {}.",
+          rows, cols, stride, needed, len
+        ),
+        MatError::InvalidStride { stride, cols } => write!(
+          f,
+          "Stride ({}) must be at least the number of columns ({}).",
+          stride, cols
+        ),
+        MatError::NotSquare {
+          operation,
+          rows,
+          cols,
+        } => write!(
+          f,
+          "{} failed! Matrix must be square. Got ({}x{})",
+          operation, rows, cols
+        ),
+        MatError::Singular { operation } => {
+          write!(f, "{} failed! Matrix is singular.", operation)
+        }
+        MatError::NotSymmetric { operation } => {
+          write!(f, "{} failed! Matrix is not symmetric.", operation)
+        }
+        MatError::NotPositiveDefinite { operation } => {
+          write!(f, "{} failed! Matrix is not positive definite.", operation)
+        }
+        MatError::InvalidComponentCount {
+          requested,
+          available,
+        } => write!(
+          f,
+          "PCA failed! Asked for {} components, but only 1 to {} are possible.",
+          requested, available
+        ),
+        MatError::NotElementwise { operation } => write!(
+          f,
+          "{} failed! Softmax works on whole rows, use Mat::apply or Activation::backward.",
+          operation
+        ),
+        MatError::NotContiguous { rows, cols, stride } => write!(
+          f,
+          "A ({}x{}) view with stride {} is not contiguous and cannot be reshaped.",
+          rows, cols, stride
+        ),
+      };
+    }
+  }
+
+  impl std::error::Error for MatError {}
+
+  // Unwraps the result of a `try_*` function, panicking with the error message
+  fn or_panic<T>(result: Result<T, MatError>) -> T {
+    return match result {
+      Ok(value) => value,
+      Err(error) => panic!("{}", error),
+    };
+  }
+
+  fn check_in_bounds(
+    operation: &'static str,
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+  ) -> Result<(), MatError> {
+    if row >= rows || col >= cols {
+      return Err(MatError::OutOfBounds {
+        operation,
+        row,
+        col,
+        rows,
+        cols,
+      });
+    }
+    return Ok(());
+  }
+
+  fn check_dimensions(rows: usize, cols: usize) -> Result<usize, MatError> {
+    if rows == 0 || cols == 0 {
+      return Err(MatError::ZeroDimension { rows, cols });
+    }
+    return rows.checked_mul(cols).ok_or(MatError::Overflow);
+  }
+
+  fn check_same_shape<T>(
+    operation: &'static str,
+    left: MatView<T>,
+    right: MatView<T>,
+  ) -> Result<(), MatError> {
+    if left.rows != right.rows || left.cols != right.cols {
+      return Err(MatError::ShapeMismatch {
+        operation,
+        left: (left.rows, left.cols),
+        right: (right.rows, right.cols),
+      });
+    }
+    return Ok(());
+  }
+
+  // Shape of the result of an elementwise operation on `left` and `right`. Like in NumPy, each
+  // dimension has to match or be 1 on one side, which repeats that operand along it: a
+  // `batch x n` matrix plus a `1 x n` row adds the row to every row of the batch.
+  fn broadcast_shape<T>(
+    operation: &'static str,
+    left: MatView<T>,
+    right: MatView<T>,
+  ) -> Result<(usize, usize), MatError> {
+    let dimension = |left: usize, right: usize| {
+      if left == right || right == 1 {
+        return Some(left);
+      }
+      if left == 1 {
+        return Some(right);
+      }
+      return None;
+    };
+    return match (
+      dimension(left.rows, right.rows),
+      dimension(left.cols, right.cols),
+    ) {
+      (Some(rows), Some(cols)) => Ok((rows, cols)),
+      _ => Err(MatError::ShapeMismatch {
+        operation,
+        left: (left.rows, left.cols),
+        right: (right.rows, right.cols),
+      }),
+    };
+  }
+
+  // Elementwise `row_kernel` on `mat1` and `mat2` after broadcasting them to a common shape
+  fn broadcast_zip<T: Element>(
+    operation: &'static str,
+    mat1: MatView<T>,
+    mat2: MatView<T>,
+    row_kernel: fn(&[T], &[T], &mut [T]),
+  ) -> Result<Mat<T>, MatError> {
+    let (rows, cols) = broadcast_shape(operation, mat1, mat2)?;
+    let mut result = Mat::zeros(rows, cols);
+    kernels::zip_into(
+      mat1,
+      mat2,
+      (rows, cols),
+      &mut result.data_stream,
+      row_kernel,
+    );
+    return Ok(result);
+  }
+
+  // Checks that a `rows x cols` window with `stride` fits into a buffer of `len` elements
+  fn check_view_fits(len: usize, rows: usize, cols: usize, stride: usize) -> Result<(), MatError> {
+    check_dimensions(rows, cols)?;
+    if cols > stride {
+      return Err(MatError::InvalidStride { stride, cols });
+    }
+    let needed = (rows - 1)
+      .checked_mul(stride)
+      .and_then(|offset| offset.checked_add(cols))
+      .ok_or(MatError::Overflow)?;
+    if needed > len {
+      return Err(MatError::BufferTooSmall {
+        rows,
+        cols,
+        stride,
+        needed,
+        len,
+      });
+    }
+    return Ok(());
   }
+
+  fn apply_row<T: Element>(activation: Activation, row: &mut [T]) {
+    if activation == Activation::Softmax {
+      // Shifting by the row maximum keeps exp() from overflowing
+      let max = row
+        .iter()
+        .fold(T::neg_infinity(), |max, &value| max.max(value));
+      let mut sum = T::zero();
+      for value in row.iter_mut() {
+        *value = (*value - max).exp();
+        sum += *value;
+      }
+      for value in row.iter_mut() {
+        *value /= sum;
+      }
+    } else if activation == Activation::Relu {
+      T::relu_row(row);
+    } else if activation != Activation::Identity {
+      for value in row.iter_mut() {
+        *value = activation.value(*value);
+      }
+    }
+  }
+
+  // `new` is only defined for the default f64 so `Mat::new(2, 3)` needs no type annotation.
+  // Other element types use `Mat::<f32>::zeros(2, 3)`.
   impl Mat {
     pub fn new(rows: usize, cols: usize) -> Mat {
-      assert!(rows > 0, "Number of rows must be greater than 0.");
-      assert!(cols > 0, "Number of columns must be greater than 0.");
-      let num_elements = match rows.checked_mul(cols) {
-        Some(result) => result,
-        None => panic!("Multiplication overflow"),
-      };
+      return Mat::zeros(rows, cols);
+    }
 
-      let data_stream = Box::into_raw(vec![0u64; num_elements].into_boxed_slice()) as *mut f64;
+    pub fn try_new(rows: usize, cols: usize) -> Result<Mat, MatError> {
+      return Mat::try_zeros(rows, cols);
+    }
+  }
 
-      return Mat {
+  impl<T: Element> Mat<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Mat<T> {
+      return or_panic(Mat::try_zeros(rows, cols));
+    }
+
+    pub fn try_zeros(rows: usize, cols: usize) -> Result<Mat<T>, MatError> {
+      let num_elements = check_dimensions(rows, cols)?;
+      return Ok(Mat {
         rows,
         cols,
         stride: cols,
-        data_stream,
-      };
+        data_stream: vec![T::zero(); num_elements],
+      });
     }
 
-    pub fn sigmoid(&mut self) {
-      for i in range!(0, (self.rows)) {
-        for j in range!(0, (self.cols)) {
-          let value_f64: f64 = safe_get!(self, i, j);
-          let sigmoid = sigmoid(value_f64);
-          self.set(i, j, sigmoid);
-        }
+    // Takes ownership of `data`, which holds the matrix row by row
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Mat<T> {
+      return or_panic(Mat::try_from_vec(rows, cols, data));
+    }
+
+    pub fn try_from_vec(rows: usize, cols: usize, data: Vec<T>) -> Result<Mat<T>, MatError> {
+      let num_elements = check_dimensions(rows, cols)?;
+      if num_elements != data.len() {
+        return Err(MatError::LengthMismatch {
+          rows,
+          cols,
+          len: data.len(),
+        });
       }
+      return Ok(Mat {
+        rows,
+        cols,
+        stride: cols,
+        data_stream: data,
+      });
+    }
+
+    pub fn sigmoid(&mut self) {
+      self.apply(Activation::Sigmoid);
+    }
+
+    // Applies `activation` in place; `Activation::Softmax` normalizes every row on its own.
+    pub fn apply(&mut self, activation: Activation) {
+      let (rows, cols, stride) = (self.rows, self.cols, self.stride);
+      kernels::for_row_blocks(
+        &mut self.data_stream,
+        rows,
+        stride,
+        rows * cols,
+        |_, block| {
+          for row in block.chunks_mut(stride) {
+            apply_row(activation, &mut row[..cols]);
+          }
+        },
+      );
+    }
+
+    pub fn print(
+      &self,
+      name: &str,
+      overwrite_padding: Option<usize>,
+      overwrite_precision: Option<usize>,
+    ) {
+      self
+        .view()
+        .print(name, overwrite_padding, overwrite_precision);
+    }
+
+    pub fn rand(&mut self, low: T, high: T) {
+      self.view_mut().rand(low, high);
+    }
+
+    pub fn fill<V>(&mut self, value: V)
+    where
+      V: Into<T> + Copy,
+    {
+      self.view_mut().fill(value);
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+      return self.try_get(row, col).ok();
+    }
+
+    pub fn try_get(&self, row: usize, col: usize) -> Result<T, MatError> {
+      check_in_bounds("Get", row, col, self.rows, self.cols)?;
+      let index = row * self.stride + col;
+      return Ok(self.data_stream[index]);
+    }
+
+    pub fn set<V>(&mut self, row: usize, col: usize, value: V)
+    where
+      V: Into<T> + Copy,
+    {
+      or_panic(self.try_set(row, col, value));
+    }
+
+    pub fn try_set<V>(&mut self, row: usize, col: usize, value: V) -> Result<(), MatError>
+    where
+      V: Into<T> + Copy,
+    {
+      check_in_bounds("Set", row, col, self.rows, self.cols)?;
+      let index = row * self.stride + col;
+      self.data_stream[index] = value.into();
+      return Ok(());
+    }
+  }
+
+  // The shape always matches the owned buffer, so it is read-only. A different shape comes from
+  // `reshape`, a view, or a new matrix.
+  impl<T> Mat<T> {
+    pub fn rows(&self) -> usize {
+      return self.rows;
+    }
+
+    pub fn cols(&self) -> usize {
+      return self.cols;
+    }
+
+    pub fn stride(&self) -> usize {
+      return self.stride;
+    }
+  }
+
+  // Views have public fields but the same accessors, so code reads the shape of a `Mat` and a
+  // view alike
+  impl<T> MatView<'_, T> {
+    pub fn rows(&self) -> usize {
+      return self.rows;
+    }
+
+    pub fn cols(&self) -> usize {
+      return self.cols;
+    }
+
+    pub fn stride(&self) -> usize {
+      return self.stride;
+    }
+  }
+
+  impl<T> MatViewMut<'_, T> {
+    pub fn rows(&self) -> usize {
+      return self.rows;
+    }
+
+    pub fn cols(&self) -> usize {
+      return self.cols;
+    }
+
+    pub fn stride(&self) -> usize {
+      return self.stride;
+    }
+  }
+
+  impl<T> AsView<T> for Mat<T> {
+    fn view(&self) -> MatView<'_, T> {
+      return MatView {
+        rows: self.rows,
+        cols: self.cols,
+        stride: self.stride,
+        data_stream: &self.data_stream,
+      };
+    }
+  }
+
+  impl<T> AsViewMut<T> for Mat<T> {
+    fn view_mut(&mut self) -> MatViewMut<'_, T> {
+      return MatViewMut {
+        rows: self.rows,
+        cols: self.cols,
+        stride: self.stride,
+        data_stream: &mut self.data_stream,
+      };
+    }
+  }
+
+  impl<'a, T: Element> MatView<'a, T> {
+    // `rows x cols` window into `data` whose rows start `stride` elements apart
+    pub fn new(data: &'a [T], rows: usize, cols: usize, stride: usize) -> MatView<'a, T> {
+      return or_panic(MatView::try_new(data, rows, cols, stride));
+    }
+
+    pub fn try_new(
+      data: &'a [T],
+      rows: usize,
+      cols: usize,
+      stride: usize,
+    ) -> Result<MatView<'a, T>, MatError> {
+      check_view_fits(data.len(), rows, cols, stride)?;
+      return Ok(MatView {
+        rows,
+        cols,
+        stride,
+        data_stream: data,
+      });
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+      return self.try_get(row, col).ok();
+    }
+
+    pub fn try_get(&self, row: usize, col: usize) -> Result<T, MatError> {
+      check_in_bounds("Get", row, col, self.rows, self.cols)?;
+      return Ok(self.data_stream[row * self.stride + col]);
     }
 
     pub fn print(
@@ -66,210 +636,271 @@ pub mod matrix {
       println!(
         "{:padding$}Memory location: {:?}",
         "",
-        self.data_stream,
+        self.data_stream.as_ptr(),
         padding = padding
       );
 
       // Calculate the maximum number of digits in column indices
       let max_col_digits = (self.cols).to_string().len();
 
-      unsafe {
-        println!("{:padding$}[", "", padding = padding);
-        for i in range!(0, self.rows) {
-          print!("{:padding$}", "", padding = padding * 2);
-          for j in range!(0, self.cols) {
-            let value = *self.data_stream.add(i * self.cols + j);
-            let value_f64: f64 = NumCast::from(value).expect("Conversion to f64 failed");
-            print!(
-              "{:<width$.precision$}",
-              value_f64,
-              width = (max_col_digits + padding + precision),
-              precision = precision
-            );
-          }
-          println!();
+      println!("{:padding$}[", "", padding = padding);
+      for i in range!(0, self.rows) {
+        print!("{:padding$}", "", padding = padding * 2);
+        for j in range!(0, self.cols) {
+          let value = self.data_stream[i * self.stride + j];
+          let value_f64: f64 = NumCast::from(value).expect("Conversion to f64 failed");
+          print!(
+            "{:<width$.precision$}",
+            value_f64,
+            width = (max_col_digits + padding + precision),
+            precision = precision
+          );
         }
-        println!("{:padding$}]", "", padding = padding);
+        println!();
       }
+      println!("{:padding$}]", "", padding = padding);
     }
 
-    pub fn rand(&self, low: f64, high: f64) {
-      let mut rng = thread_rng();
+    // Copies the viewed elements into a new contiguous matrix
+    pub fn to_mat(&self) -> Mat<T> {
+      let mut result = Mat::zeros(self.rows, self.cols);
       for i in range!(0, self.rows) {
         for j in range!(0, self.cols) {
-          let random_value = rng.gen_range(low..=high);
-          let index = i * (self.cols) + j;
-          unsafe {
-            self.data_stream.add(index).write(random_value);
-          }
+          result[(i, j)] = self.data_stream[i * self.stride + j];
         }
       }
+      return result;
     }
+  }
 
-    pub fn fill<T>(&mut self, value: T)
-    where
-      T: Into<f64> + Copy,
-    {
-      let value_f64 = value.into();
-      let data_stream =
-        unsafe { std::slice::from_raw_parts_mut(self.data_stream, self.rows * self.cols) };
+  impl<T: Copy> AsView<T> for MatView<'_, T> {
+    fn view(&self) -> MatView<'_, T> {
+      return *self;
+    }
+  }
 
-      for element in data_stream.iter_mut() {
-        *element = value_f64;
-      }
+  impl<'a, T: Element> MatViewMut<'a, T> {
+    // `rows x cols` mutable window into `data` whose rows start `stride` elements apart
+    pub fn new(data: &'a mut [T], rows: usize, cols: usize, stride: usize) -> MatViewMut<'a, T> {
+      return or_panic(MatViewMut::try_new(data, rows, cols, stride));
     }
 
-    pub fn get(&self, row: usize, col: usize) -> Option<f64> {
-      assert!(
-        row < self.rows,
-        "Get failed! Supplied row Index {} is out of bounds. Matrix has {} rows.",
-        row,
-        self.rows
-      );
-      assert!(
-        col < self.cols,
-        "Get failed! Supplied column Index {} is out of bounds. Matrix has {} columns.",
-        col,
-        self.cols
-      );
-      let index = row * self.stride + col;
-      unsafe {
-        return Some(self.data_stream.add(index).read().clone());
-      }
+    pub fn try_new(
+      data: &'a mut [T],
+      rows: usize,
+      cols: usize,
+      stride: usize,
+    ) -> Result<MatViewMut<'a, T>, MatError> {
+      check_view_fits(data.len(), rows, cols, stride)?;
+      return Ok(MatViewMut {
+        rows,
+        cols,
+        stride,
+        data_stream: data,
+      });
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+      return self.try_get(row, col).ok();
+    }
+
+    pub fn try_get(&self, row: usize, col: usize) -> Result<T, MatError> {
+      check_in_bounds("Get", row, col, self.rows, self.cols)?;
+      return Ok(self.data_stream[row * self.stride + col]);
     }
 
-    pub fn set<T>(&mut self, row: usize, col: usize, value: T)
+    pub fn set<V>(&mut self, row: usize, col: usize, value: V)
     where
-      T: Into<f64> + Copy,
+      V: Into<T> + Copy,
     {
-      let value_f64 = value.into();
-      let rows_usize = self.rows;
-      let cols_usize = self.cols;
+      or_panic(self.try_set(row, col, value));
+    }
 
-      assert!(
-        row < rows_usize,
-        "Set failed! Supplied row Index {} is out of bounds. Matrix has {} rows.",
-        row,
-        rows_usize
-      );
-      assert!(
-        col < cols_usize,
-        "Set failed! Supplied column Index {} is out of bounds. Matrix has {} columns.",
-        col,
-        cols_usize
-      );
+    pub fn try_set<V>(&mut self, row: usize, col: usize, value: V) -> Result<(), MatError>
+    where
+      V: Into<T> + Copy,
+    {
+      check_in_bounds("Set", row, col, self.rows, self.cols)?;
+      self.data_stream[row * self.stride + col] = value.into();
+      return Ok(());
+    }
 
-      let index = row * cols_usize + col;
-      unsafe {
-        self.data_stream.add(index).write(value_f64);
+    pub fn rand(&mut self, low: T, high: T) {
+      let mut rng = thread_rng();
+      for i in range!(0, self.rows) {
+        for j in range!(0, self.cols) {
+          let random_value = rng.gen_range(low..=high);
+          let index = i * (self.stride) + j;
+          self.data_stream[index] = random_value;
+        }
       }
     }
-    fn drop(&mut self) {
-      let num_elements = self.rows * self.cols;
-      unsafe {
-        Vec::from_raw_parts(self.data_stream, 0, num_elements);
+
+    // Only touches the elements inside the view, the gaps between its rows keep their values
+    pub fn fill<V>(&mut self, value: V)
+    where
+      V: Into<T> + Copy,
+    {
+      let value = value.into();
+      for i in range!(0, self.rows) {
+        let start = i * self.stride;
+        T::fill_row(&mut self.data_stream[start..start + self.cols], value);
       }
     }
   }
 
-  pub fn addition(mat1: &Mat, mat2: &Mat) -> Mat {
-    assert!(
-      mat1.rows == mat2.rows && mat1.cols == mat2.cols,
-      "Matrix dimensions must match. Got Mat1: ({}x{}) and Mat2: ({}x{})",
-      mat1.rows,
-      mat1.cols,
-      mat2.rows,
-      mat2.cols
-    );
-    let rows = mat1.rows;
-    let cols = mat1.cols;
-    let mut result = Mat::new(rows, cols);
-
-    for i in 0..mat1.rows {
-      for j in 0..mat1.cols {
-        let value1 = safe_get!(mat1, i, j);
-        let value2 = safe_get!(mat2, i, j);
-        let sum = value1 + value2;
-        // This might make problems, because we dont handle the error case
-        result.set(i, j, sum);
-      }
+  impl<T> AsViewMut<T> for MatViewMut<'_, T> {
+    fn view_mut(&mut self) -> MatViewMut<'_, T> {
+      return MatViewMut {
+        rows: self.rows,
+        cols: self.cols,
+        stride: self.stride,
+        data_stream: self.data_stream,
+      };
     }
+  }
 
-    return result;
+  impl<T> AsView<T> for MatViewMut<'_, T> {
+    fn view(&self) -> MatView<'_, T> {
+      return MatView {
+        rows: self.rows,
+        cols: self.cols,
+        stride: self.stride,
+        data_stream: self.data_stream,
+      };
+    }
   }
-  pub fn subtraction(mat1: &Mat, mat2: &Mat) -> Mat {
-    assert!(
-      mat1.rows == mat2.rows && mat1.cols == mat2.cols,
-      "Matrix dimensions must match. Got Mat1: ({}x{}) and Mat2: ({}x{})",
-      mat1.rows,
-      mat1.cols,
-      mat2.rows,
-      mat2.cols
-    );
 
-    let rows = mat1.rows;
-    let cols = mat1.cols;
-    let mut result = Mat::new(rows, cols);
+  // The elementwise functions broadcast their operands, see `broadcast_shape`
+  pub fn addition<T: Element, A: AsView<T>, B: AsView<T>>(mat1: &A, mat2: &B) -> Mat<T> {
+    return or_panic(try_addition(mat1, mat2));
+  }
 
-    for i in 0..rows {
-      for j in 0..cols {
-        let value1 = safe_get!(mat1, i, j);
-        let value2 = safe_get!(mat2, i, j);
-        let diff = value1.sub(value2);
-        result.set(i, j, diff);
-      }
+  pub fn try_addition<T: Element, A: AsView<T>, B: AsView<T>>(
+    mat1: &A,
+    mat2: &B,
+  ) -> Result<Mat<T>, MatError> {
+    return broadcast_zip("Addition", mat1.view(), mat2.view(), T::add_row);
+  }
+
+  pub fn subtraction<T: Element, A: AsView<T>, B: AsView<T>>(mat1: &A, mat2: &B) -> Mat<T> {
+    return or_panic(try_subtraction(mat1, mat2));
+  }
+
+  pub fn try_subtraction<T: Element, A: AsView<T>, B: AsView<T>>(
+    mat1: &A,
+    mat2: &B,
+  ) -> Result<Mat<T>, MatError> {
+    return broadcast_zip("Subtraction", mat1.view(), mat2.view(), T::sub_row);
+  }
+
+  // Elementwise product, not to be confused with the matrix product `dot_product`
+  pub fn hadamard<T: Element, A: AsView<T>, B: AsView<T>>(mat1: &A, mat2: &B) -> Mat<T> {
+    return or_panic(try_hadamard(mat1, mat2));
+  }
+
+  pub fn try_hadamard<T: Element, A: AsView<T>, B: AsView<T>>(
+    mat1: &A,
+    mat2: &B,
+  ) -> Result<Mat<T>, MatError> {
+    return broadcast_zip("Hadamard product", mat1.view(), mat2.view(), T::mul_row);
+  }
+
+  pub fn elementwise_div<T: Element, A: AsView<T>, B: AsView<T>>(mat1: &A, mat2: &B) -> Mat<T> {
+    return or_panic(try_elementwise_div(mat1, mat2));
+  }
+
+  pub fn try_elementwise_div<T: Element, A: AsView<T>, B: AsView<T>>(
+    mat1: &A,
+    mat2: &B,
+  ) -> Result<Mat<T>, MatError> {
+    return broadcast_zip("Division", mat1.view(), mat2.view(), T::div_row);
+  }
+
+  pub fn dot_product<T: Element, A: AsView<T>, B: AsView<T>>(mat1: &A, mat2: &B) -> Mat<T> {
+    return or_panic(try_dot_product(mat1, mat2));
+  }
+
+  // For Multiplications mat1 cols must match mat2 rows
+  pub fn try_dot_product<T: Element, A: AsView<T>, B: AsView<T>>(
+    mat1: &A,
+    mat2: &B,
+  ) -> Result<Mat<T>, MatError> {
+    let (mat1, mat2) = (mat1.view(), mat2.view());
+    if mat1.cols != mat2.rows {
+      return Err(MatError::ShapeMismatch {
+        operation: "Dot product",
+        left: (mat1.rows, mat1.cols),
+        right: (mat2.rows, mat2.cols),
+      });
     }
+    let mut result = Mat::zeros(mat1.rows, mat2.cols);
+    kernels::matmul(mat1, mat2, &mut result.data_stream);
+    return Ok(result);
+  }
 
-    result
+  pub fn dot_product_transposed<T: Element, A: AsView<T>, B: AsView<T>>(
+    mat1: &A,
+    mat2: &B,
+  ) -> Mat<T> {
+    return or_panic(try_dot_product_transposed(mat1, mat2));
   }
 
-  pub fn dot_product(mat1: &Mat, mat2: &Mat) -> Mat {
-    assert!(
-        mat1.cols == mat2.rows,
-        // For Multiplications mat1 cols must match mat2 rows
-        "For Multiplications Mat1 Cols:({}) must match Mat2 Rows: ({})! \n . Got Mat1: ({}x{}) and Mat2: ({}x{})", mat1.cols, mat2.rows, mat1.rows, mat1.cols, mat2.rows, mat2.cols
-    );
-    let mut result: Mat = Mat::new(mat1.rows, mat2.cols);
-
-    for i in 0..mat1.rows {
-      for j in 0..mat2.cols {
-        let mut sum = 0.0;
-        for k in 0..mat1.cols {
-          let value1 = safe_get!(mat1, i, k);
-          let value2 = safe_get!(mat2, k, j);
-          sum = sum + (value1 * value2);
-          println!("{} {} {}", value1, value2, sum)
-        }
-        result.set(i, j, sum);
-      }
+  // mat1 * mat2^T without building the transpose, so both operands need the same number of
+  // columns. Backprop uses it to push deltas back through the weights.
+  pub fn try_dot_product_transposed<T: Element, A: AsView<T>, B: AsView<T>>(
+    mat1: &A,
+    mat2: &B,
+  ) -> Result<Mat<T>, MatError> {
+    let (mat1, mat2) = (mat1.view(), mat2.view());
+    if mat1.cols != mat2.cols {
+      return Err(MatError::ShapeMismatch {
+        operation: "Dot product",
+        left: (mat1.rows, mat1.cols),
+        right: (mat2.cols, mat2.rows),
+      });
     }
+    let mut result = Mat::zeros(mat1.rows, mat2.rows);
+    kernels::matmul_transposed(mat1, mat2, &mut result.data_stream);
+    return Ok(result);
+  }
 
-    return result;
+  pub fn mat_row<T: Element, M: AsView<T>>(m: &M, row: usize) -> MatView<'_, T> {
+    return or_panic(try_mat_row(m, row));
   }
-  pub fn mat_row(m: &Mat, row: usize) -> Mat {
-    let index = row * m.stride;
-    return Mat {
+
+  pub fn try_mat_row<T: Element, M: AsView<T>>(
+    m: &M,
+    row: usize,
+  ) -> Result<MatView<'_, T>, MatError> {
+    let view = m.view();
+    check_in_bounds("Row", row, 0, view.rows, view.cols)?;
+    let index = row * view.stride;
+    return Ok(MatView {
       rows: 1,
-      cols: m.cols,
-      stride: m.stride,
-      data_stream: unsafe { m.data_stream.add(index) },
-    };
+      cols: view.cols,
+      stride: view.stride,
+      data_stream: &view.data_stream[index..index + view.cols],
+    });
   }
 
-  pub fn mat_copy(m_dest: &mut Mat, m_src: &Mat) {
-    assert!(
-      m_dest.cols == m_src.cols && m_dest.rows == m_src.rows,
-      "Matrices must have the same dimensions. Got Mat1: ({}x{}) and Mat2: ({}x{})",
-      m_dest.rows,
-      m_dest.cols,
-      m_src.rows,
-      m_src.cols
-    );
+  pub fn mat_copy<T: Element, D: AsViewMut<T>, S: AsView<T>>(m_dest: &mut D, m_src: &S) {
+    or_panic(try_mat_copy(m_dest, m_src));
+  }
+
+  pub fn try_mat_copy<T: Element, D: AsViewMut<T>, S: AsView<T>>(
+    m_dest: &mut D,
+    m_src: &S,
+  ) -> Result<(), MatError> {
+    let mut m_dest = m_dest.view_mut();
+    let m_src = m_src.view();
+    check_same_shape("Copy", m_dest.view(), m_src)?;
     for i in range!(0, m_src.rows) {
       for j in range!(0, m_src.cols) {
-        let value = safe_get!(m_src, i, j);
-        m_dest.set(i, j, value);
+        let value = m_src.try_get(i, j)?;
+        m_dest.try_set(i, j, value)?;
       }
     }
+    return Ok(());
   }
 }
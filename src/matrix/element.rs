@@ -0,0 +1,95 @@
+// Number types a `Mat` can hold. The row kernels default to plain loops; f64 overrides them
+// with the SIMD paths in `simd`.
+use super::simd;
+use num_traits::{Float, NumAssign};
+use rand::distributions::uniform::SampleUniform;
+use std::fmt;
+
+pub trait Element:
+  Float + NumAssign + SampleUniform + Default + fmt::Debug + fmt::Display + Send + Sync + 'static
+{
+  // out = a + b, element by element
+  fn add_row(a: &[Self], b: &[Self], out: &mut [Self]) {
+    for ((value, &x), &y) in out.iter_mut().zip(a).zip(b) {
+      *value = x + y;
+    }
+  }
+
+  // out = a - b, element by element
+  fn sub_row(a: &[Self], b: &[Self], out: &mut [Self]) {
+    for ((value, &x), &y) in out.iter_mut().zip(a).zip(b) {
+      *value = x - y;
+    }
+  }
+
+  // out = a * b, element by element
+  fn mul_row(a: &[Self], b: &[Self], out: &mut [Self]) {
+    for ((value, &x), &y) in out.iter_mut().zip(a).zip(b) {
+      *value = x * y;
+    }
+  }
+
+  // out = a / b, element by element
+  fn div_row(a: &[Self], b: &[Self], out: &mut [Self]) {
+    for ((value, &x), &y) in out.iter_mut().zip(a).zip(b) {
+      *value = x / y;
+    }
+  }
+
+  fn fill_row(out: &mut [Self], value: Self) {
+    for element in out.iter_mut() {
+      *element = value;
+    }
+  }
+
+  fn relu_row(values: &mut [Self]) {
+    for value in values.iter_mut() {
+      *value = value.max(Self::zero());
+    }
+  }
+
+  fn sum_row(values: &[Self]) -> Self {
+    let mut sum = Self::zero();
+    for &value in values {
+      sum += value;
+    }
+    return sum;
+  }
+
+  // Turns an f64 constant into this type; every constant the crate uses fits into an f32
+  fn constant(value: f64) -> Self {
+    return Self::from(value).expect("Constant does not fit into the element type");
+  }
+}
+
+impl Element for f32 {}
+
+impl Element for f64 {
+  fn add_row(a: &[f64], b: &[f64], out: &mut [f64]) {
+    simd::add(a, b, out);
+  }
+
+  fn sub_row(a: &[f64], b: &[f64], out: &mut [f64]) {
+    simd::sub(a, b, out);
+  }
+
+  fn mul_row(a: &[f64], b: &[f64], out: &mut [f64]) {
+    simd::mul(a, b, out);
+  }
+
+  fn div_row(a: &[f64], b: &[f64], out: &mut [f64]) {
+    simd::div(a, b, out);
+  }
+
+  fn fill_row(out: &mut [f64], value: f64) {
+    simd::fill(out, value);
+  }
+
+  fn relu_row(values: &mut [f64]) {
+    simd::relu(values);
+  }
+
+  fn sum_row(values: &[f64]) -> f64 {
+    return simd::sum(values);
+  }
+}
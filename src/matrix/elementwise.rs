@@ -0,0 +1,83 @@
+// Elementwise helpers built on closures: map, zip_map and the scalar versions of + and *. All
+// of them walk views row by row, so strided windows only touch their own elements.
+use super::{
+  broadcast_shape, or_panic, AsView, AsViewMut, Element, Mat, MatError, MatView, MatViewMut,
+};
+
+impl<T: Element> MatView<'_, T> {
+  // New matrix holding `f(x)` for every element x
+  pub fn map(&self, f: impl Fn(T) -> T) -> Mat<T> {
+    let mut result = self.to_mat();
+    result.map_inplace(f);
+    return result;
+  }
+
+  // New matrix holding `f(x, y)` for every pair of matching elements; `other` broadcasts
+  // like it does in `addition`
+  pub fn zip_map<B: AsView<T>>(&self, other: &B, f: impl Fn(T, T) -> T) -> Mat<T> {
+    return or_panic(self.try_zip_map(other, f));
+  }
+
+  pub fn try_zip_map<B: AsView<T>>(
+    &self,
+    other: &B,
+    f: impl Fn(T, T) -> T,
+  ) -> Result<Mat<T>, MatError> {
+    let other = other.view();
+    let (rows, cols) = broadcast_shape("Zip map", *self, other)?;
+    let mut result = Mat::zeros(rows, cols);
+    for i in 0..rows {
+      for j in 0..cols {
+        // A broadcast operand has a single row or column, which the remainder always picks
+        let x = self[(i % self.rows, j % self.cols)];
+        let y = other[(i % other.rows, j % other.cols)];
+        result[(i, j)] = f(x, y);
+      }
+    }
+    return Ok(result);
+  }
+}
+
+impl<T: Element> MatViewMut<'_, T> {
+  // Replaces every element x by `f(x)`
+  pub fn map_inplace(&mut self, f: impl Fn(T) -> T) {
+    for i in 0..self.rows {
+      let start = i * self.stride;
+      for value in self.data_stream[start..start + self.cols].iter_mut() {
+        *value = f(*value);
+      }
+    }
+  }
+}
+
+impl<T: Element> Mat<T> {
+  pub fn map(&self, f: impl Fn(T) -> T) -> Mat<T> {
+    return self.view().map(f);
+  }
+
+  pub fn map_inplace(&mut self, f: impl Fn(T) -> T) {
+    self.view_mut().map_inplace(f);
+  }
+
+  pub fn zip_map<B: AsView<T>>(&self, other: &B, f: impl Fn(T, T) -> T) -> Mat<T> {
+    return self.view().zip_map(other, f);
+  }
+
+  pub fn try_zip_map<B: AsView<T>>(
+    &self,
+    other: &B,
+    f: impl Fn(T, T) -> T,
+  ) -> Result<Mat<T>, MatError> {
+    return self.view().try_zip_map(other, f);
+  }
+}
+
+// `value` added to every element of `mat`
+pub fn add_scalar<T: Element, A: AsView<T>>(mat: &A, value: T) -> Mat<T> {
+  return mat.view().map(|x| x + value);
+}
+
+// Every element of `mat` multiplied by `factor`
+pub fn scale<T: Element, A: AsView<T>>(mat: &A, factor: T) -> Mat<T> {
+  return mat.view().map(|x| x * factor);
+}
@@ -0,0 +1,226 @@
+// Inner loops of the matrix kernels. Operands come in as views and are walked through slices
+// only, so the compiler can drop every bounds check from the hot loops.
+use super::{Element, MatView};
+
+// Below this many multiply-adds (or elements, for elementwise kernels) spawning threads costs
+// more than it saves, so the `parallel` feature keeps such work on the calling thread
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_WORK: usize = 1 << 18;
+
+// Block sizes of the product: a KC_BLOCK x NC_BLOCK panel of packed B (256 KiB) stays in L2
+// while every row of A streams past it
+const KC_BLOCK: usize = 256;
+const NC_BLOCK: usize = 128;
+// Packing B costs as much as a product with this many rows of A, so fewer rows skip it
+const PACK_MIN_ROWS: usize = 8;
+
+// Writes `a * b` into `out`, a contiguous `a.rows x b.cols` buffer that starts zeroed.
+// Shapes have already been checked by the caller.
+pub(super) fn matmul<T: Element>(a: MatView<T>, b: MatView<T>, out: &mut [T]) {
+  let (m, k, n) = (a.rows, a.cols, b.cols);
+  if m < PACK_MIN_ROWS {
+    for_row_blocks(out, m, n, m * k * n, |first_row, block| {
+      matmul_unpacked(a, b, first_row, block);
+    });
+    return;
+  }
+  let packed = pack_transposed(b);
+  let bt = MatView {
+    rows: n,
+    cols: k,
+    stride: k,
+    data_stream: &packed,
+  };
+  for_row_blocks(out, m, n, m * k * n, |first_row, block| {
+    matmul_packed(a, bt, first_row, block);
+  });
+}
+
+// Writes `a * b^T` into `out`, a contiguous `a.rows x b.rows` buffer that starts zeroed. The
+// rows of `b` are the columns of `b^T`, so they are used in place without packing.
+pub(super) fn matmul_transposed<T: Element>(a: MatView<T>, b: MatView<T>, out: &mut [T]) {
+  let (m, k, n) = (a.rows, a.cols, b.rows);
+  for_row_blocks(out, m, n, m * k * n, |first_row, block| {
+    matmul_packed(a, b, first_row, block);
+  });
+}
+
+// Computes the output rows in `block`, the first of which is row `first_row` of `a * bt^T`.
+// Row j of `bt` is column j of the right operand, as laid out by `pack_transposed`.
+fn matmul_packed<T: Element>(a: MatView<T>, bt: MatView<T>, first_row: usize, block: &mut [T]) {
+  let (k, n) = (bt.cols, bt.rows);
+  for p0 in (0..k).step_by(KC_BLOCK) {
+    let p1 = (p0 + KC_BLOCK).min(k);
+    for j0 in (0..n).step_by(NC_BLOCK) {
+      let j1 = (j0 + NC_BLOCK).min(n);
+      let panel = &bt.data_stream[j0 * bt.stride..];
+      for (i, out_row) in block.chunks_exact_mut(n).enumerate() {
+        let start = (first_row + i) * a.stride;
+        let a_row = &a.data_stream[start + p0..start + p1];
+        row_times_panel(a_row, panel, bt.stride, p0, &mut out_row[j0..j1]);
+      }
+    }
+  }
+}
+
+// Adds `a[i][p] * b[p]` onto output row `i` for every p. Reads B row by row as it is, which
+// beats packing when only a handful of rows of A will use it.
+fn matmul_unpacked<T: Element>(a: MatView<T>, b: MatView<T>, first_row: usize, block: &mut [T]) {
+  let n = b.cols;
+  for (i, out_row) in block.chunks_exact_mut(n).enumerate() {
+    let start = (first_row + i) * a.stride;
+    let a_row = &a.data_stream[start..start + a.cols];
+    for (&x, b_row) in a_row.iter().zip(b.data_stream.chunks(b.stride)) {
+      for (value, &y) in out_row.iter_mut().zip(&b_row[..n]) {
+        *value += x * y;
+      }
+    }
+  }
+}
+
+// Copies `b` so that each of its columns is one contiguous run of `b.rows` values. The
+// product then reads both operands front to back instead of striding down B.
+fn pack_transposed<T: Element>(b: MatView<T>) -> Vec<T> {
+  let (k, n) = (b.rows, b.cols);
+  let mut packed = vec![T::zero(); k * n];
+  for (p, row) in b.data_stream.chunks(b.stride).take(k).enumerate() {
+    for (j, &value) in row[..n].iter().enumerate() {
+      packed[j * k + p] = value;
+    }
+  }
+  return packed;
+}
+
+// Adds `a_row` times every packed column of `panel` onto `out_row`. Columns start `stride`
+// elements apart, and only the part of each column between `offset` and
+// `offset + a_row.len()` takes part, the rest belongs to other k blocks. Four columns share
+// every load of `a_row`.
+fn row_times_panel<T: Element>(
+  a_row: &[T],
+  panel: &[T],
+  stride: usize,
+  offset: usize,
+  out_row: &mut [T],
+) {
+  let len = a_row.len();
+  let column = |j: usize| &panel[j * stride + offset..j * stride + offset + len];
+
+  let mut out_chunks = out_row.chunks_exact_mut(4);
+  let mut j = 0;
+  for out in &mut out_chunks {
+    let sums = dot4(
+      a_row,
+      column(j),
+      column(j + 1),
+      column(j + 2),
+      column(j + 3),
+    );
+    for (value, sum) in out.iter_mut().zip(sums) {
+      *value += sum;
+    }
+    j += 4;
+  }
+  for value in out_chunks.into_remainder() {
+    *value += dot(a_row, column(j));
+    j += 1;
+  }
+}
+
+fn dot4<T: Element>(a: &[T], b0: &[T], b1: &[T], b2: &[T], b3: &[T]) -> [T; 4] {
+  let mut sums = [T::zero(); 4];
+  for ((((&x, &y0), &y1), &y2), &y3) in a.iter().zip(b0).zip(b1).zip(b2).zip(b3) {
+    sums[0] += x * y0;
+    sums[1] += x * y1;
+    sums[2] += x * y2;
+    sums[3] += x * y3;
+  }
+  return sums;
+}
+
+fn dot<T: Element>(a: &[T], b: &[T]) -> T {
+  let mut sum = T::zero();
+  for (&x, &y) in a.iter().zip(b) {
+    sum += x * y;
+  }
+  return sum;
+}
+
+// Calls `f(first_row, block)` on consecutive blocks of whole rows of `out`, a buffer of `rows`
+// rows that are `stride` elements apart. With the `parallel` feature, `work` above
+// PARALLEL_MIN_WORK is split into one block per available core; otherwise `f` sees all of
+// `out` at once.
+pub(super) fn for_row_blocks<T, F>(out: &mut [T], rows: usize, stride: usize, work: usize, f: F)
+where
+  T: Send,
+  F: Fn(usize, &mut [T]) + Sync,
+{
+  #[cfg(feature = "parallel")]
+  {
+    let threads = if work >= PARALLEL_MIN_WORK {
+      thread_count()
+    } else {
+      1
+    };
+    if threads > 1 && rows > 1 {
+      let rows_per_block = rows.div_ceil(threads);
+      std::thread::scope(|scope| {
+        for (index, block) in out.chunks_mut(rows_per_block * stride).enumerate() {
+          let f = &f;
+          scope.spawn(move || f(index * rows_per_block, block));
+        }
+      });
+      return;
+    }
+  }
+  #[cfg(not(feature = "parallel"))]
+  let _ = (rows, stride, work);
+  f(0, out);
+}
+
+// Looking up the core count reads cgroup files on Linux, so it is done only once
+#[cfg(feature = "parallel")]
+fn thread_count() -> usize {
+  static THREADS: std::sync::OnceLock<usize> = std::sync::OnceLock::new();
+  return *THREADS
+    .get_or_init(|| std::thread::available_parallelism().map_or(1, |count| count.get()));
+}
+
+// Runs `f(a_row, b_row, out_row)` for every row of `out`, a contiguous `rows x cols` buffer.
+// `f` is one of the row kernels of `Element`. Either operand may broadcast: a single row is
+// reused for every output row, and a single column is repeated across the whole row.
+pub(super) fn zip_into<T, F>(
+  a: MatView<T>,
+  b: MatView<T>,
+  (rows, cols): (usize, usize),
+  out: &mut [T],
+  f: F,
+) where
+  T: Element,
+  F: Fn(&[T], &[T], &mut [T]) + Sync,
+{
+  for_row_blocks(out, rows, cols, rows * cols, |first_row, block| {
+    // Rows of a one column operand get spread into these buffers
+    let (mut a_wide, mut b_wide) = (Vec::new(), Vec::new());
+    for (i, out_row) in block.chunks_exact_mut(cols).enumerate() {
+      let a_row = broadcast_row(a, first_row + i, cols, &mut a_wide);
+      let b_row = broadcast_row(b, first_row + i, cols, &mut b_wide);
+      f(a_row, b_row, out_row);
+    }
+  });
+}
+
+// Row `i` of the broadcast of `mat` to `cols` columns
+fn broadcast_row<'a, T: Element>(
+  mat: MatView<'a, T>,
+  i: usize,
+  cols: usize,
+  wide: &'a mut Vec<T>,
+) -> &'a [T] {
+  let start = if mat.rows == 1 { 0 } else { i * mat.stride };
+  if mat.cols == cols {
+    return &mat.data_stream[start..start + cols];
+  }
+  wide.resize(cols, T::zero());
+  T::fill_row(wide, mat.data_stream[start]);
+  return wide;
+}
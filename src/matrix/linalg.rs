@@ -0,0 +1,451 @@
+// Dense linear algebra: LU decomposition with partial pivoting and what it gives for free,
+// i.e. determinants, inverses and solutions of linear systems, plus QR and Cholesky
+// decompositions and least squares fits.
+use super::{or_panic, AsView, Element, Mat, MatError, MatView, Norm};
+
+// Checks that `mat` is square and returns its size
+fn check_square<T>(operation: &'static str, mat: MatView<T>) -> Result<usize, MatError> {
+  if mat.rows != mat.cols {
+    return Err(MatError::NotSquare {
+      operation,
+      rows: mat.rows,
+      cols: mat.cols,
+    });
+  }
+  return Ok(mat.rows);
+}
+
+// Values at most this far from zero count as zero: rounding leaves about n ulps of the largest
+// element behind in a pivot that is zero in exact arithmetic
+fn rounding_tolerance<T: Element>(mat: MatView<T>) -> T {
+  let largest = mat.norm(Norm::Max);
+  return T::epsilon() * T::constant(mat.rows.max(mat.cols) as f64) * largest;
+}
+
+pub(super) fn check_symmetric<T: Element>(
+  operation: &'static str,
+  mat: MatView<T>,
+) -> Result<(), MatError> {
+  let n = check_square(operation, mat)?;
+  let tolerance = rounding_tolerance(mat);
+  for i in 0..n {
+    for j in 0..i {
+      if (mat[(i, j)] - mat[(j, i)]).abs() > tolerance {
+        return Err(MatError::NotSymmetric { operation });
+      }
+    }
+  }
+  return Ok(());
+}
+
+impl<T: Element> Mat<T> {
+  // The `n x n` identity matrix
+  pub fn identity(n: usize) -> Mat<T> {
+    let mut result = Mat::zeros(n, n);
+    for i in 0..n {
+      result[(i, i)] = T::one();
+    }
+    return result;
+  }
+}
+
+// P A = L U, where P reorders the rows of A, L is lower triangular with ones on its diagonal
+// and U is upper triangular. Both triangles share one matrix.
+#[derive(Clone, Debug)]
+pub struct Lu<T = f64> {
+  factors: Mat<T>,
+  // Row i of P A is row permutation[i] of A
+  permutation: Vec<usize>,
+  // +1 or -1, depending on whether P swaps an even or odd number of rows
+  sign: T,
+  singular: bool,
+}
+
+impl<T: Element> Lu<T> {
+  pub fn l(&self) -> Mat<T> {
+    let n = self.factors.rows;
+    let mut l = Mat::identity(n);
+    for i in 1..n {
+      for j in 0..i {
+        l[(i, j)] = self.factors[(i, j)];
+      }
+    }
+    return l;
+  }
+
+  pub fn u(&self) -> Mat<T> {
+    let n = self.factors.rows;
+    let mut u = Mat::zeros(n, n);
+    for i in 0..n {
+      for j in i..n {
+        u[(i, j)] = self.factors[(i, j)];
+      }
+    }
+    return u;
+  }
+
+  pub fn permutation(&self) -> &[usize] {
+    return &self.permutation;
+  }
+
+  // The permutation as a matrix P, so that P A = L U
+  pub fn p(&self) -> Mat<T> {
+    let n = self.factors.rows;
+    let mut p = Mat::zeros(n, n);
+    for (i, &row) in self.permutation.iter().enumerate() {
+      p[(i, row)] = T::one();
+    }
+    return p;
+  }
+
+  // True when a pivot was zero, up to rounding
+  pub fn is_singular(&self) -> bool {
+    return self.singular;
+  }
+
+  // Zero for singular matrices, otherwise the product of the pivots
+  pub fn det(&self) -> T {
+    if self.singular {
+      return T::zero();
+    }
+    let mut det = self.sign;
+    for i in 0..self.factors.rows {
+      det *= self.factors[(i, i)];
+    }
+    return det;
+  }
+
+  // X with A X = B, one column of X for every column of `b`
+  pub fn solve<B: AsView<T>>(&self, b: &B) -> Mat<T> {
+    return or_panic(self.try_solve(b));
+  }
+
+  pub fn try_solve<B: AsView<T>>(&self, b: &B) -> Result<Mat<T>, MatError> {
+    let b = b.view();
+    let n = self.factors.rows;
+    if b.rows != n {
+      return Err(MatError::ShapeMismatch {
+        operation: "Solve",
+        left: (n, n),
+        right: (b.rows, b.cols),
+      });
+    }
+    if self.singular {
+      return Err(MatError::Singular { operation: "Solve" });
+    }
+    // Forward substitution through L on the reordered right hand side, then back substitution
+    // through U, one column at a time
+    let mut x = Mat::zeros(n, b.cols);
+    for col in 0..b.cols {
+      for i in 0..n {
+        let mut value = b[(self.permutation[i], col)];
+        for j in 0..i {
+          value -= self.factors[(i, j)] * x[(j, col)];
+        }
+        x[(i, col)] = value;
+      }
+      for i in (0..n).rev() {
+        let mut value = x[(i, col)];
+        for j in i + 1..n {
+          value -= self.factors[(i, j)] * x[(j, col)];
+        }
+        x[(i, col)] = value / self.factors[(i, i)];
+      }
+    }
+    return Ok(x);
+  }
+
+  pub fn inverse(&self) -> Mat<T> {
+    return or_panic(self.try_inverse());
+  }
+
+  pub fn try_inverse(&self) -> Result<Mat<T>, MatError> {
+    if self.singular {
+      return Err(MatError::Singular {
+        operation: "Inverse",
+      });
+    }
+    return self.try_solve(&Mat::identity(self.factors.rows));
+  }
+}
+
+pub fn lu<T: Element, A: AsView<T>>(mat: &A) -> Lu<T> {
+  return or_panic(try_lu(mat));
+}
+
+// Fails only for matrices that are not square. A singular matrix still decomposes, but its
+// `Lu` refuses to solve or invert.
+pub fn try_lu<T: Element, A: AsView<T>>(mat: &A) -> Result<Lu<T>, MatError> {
+  let mat = mat.view();
+  let n = check_square("LU decomposition", mat)?;
+  let tolerance = rounding_tolerance(mat);
+  let mut factors = mat.to_mat();
+  let mut permutation: Vec<usize> = (0..n).collect();
+  let mut sign = T::one();
+  let mut singular = false;
+
+  for k in 0..n {
+    // Partial pivoting: the largest remaining element of column k becomes the pivot
+    let mut pivot_row = k;
+    for i in k + 1..n {
+      if factors[(i, k)].abs() > factors[(pivot_row, k)].abs() {
+        pivot_row = i;
+      }
+    }
+    if pivot_row != k {
+      for j in 0..n {
+        let value = factors[(k, j)];
+        factors[(k, j)] = factors[(pivot_row, j)];
+        factors[(pivot_row, j)] = value;
+      }
+      permutation.swap(k, pivot_row);
+      sign = -sign;
+    }
+
+    let pivot = factors[(k, k)];
+    if pivot.abs() <= tolerance {
+      singular = true;
+      continue;
+    }
+    for i in k + 1..n {
+      let factor = factors[(i, k)] / pivot;
+      factors[(i, k)] = factor;
+      for j in k + 1..n {
+        let value = factors[(k, j)];
+        factors[(i, j)] -= factor * value;
+      }
+    }
+  }
+
+  return Ok(Lu {
+    factors,
+    permutation,
+    sign,
+    singular,
+  });
+}
+
+pub fn det<T: Element, A: AsView<T>>(mat: &A) -> T {
+  return or_panic(try_det(mat));
+}
+
+pub fn try_det<T: Element, A: AsView<T>>(mat: &A) -> Result<T, MatError> {
+  check_square("Determinant", mat.view())?;
+  return Ok(try_lu(mat)?.det());
+}
+
+pub fn inverse<T: Element, A: AsView<T>>(mat: &A) -> Mat<T> {
+  return or_panic(try_inverse(mat));
+}
+
+pub fn try_inverse<T: Element, A: AsView<T>>(mat: &A) -> Result<Mat<T>, MatError> {
+  check_square("Inverse", mat.view())?;
+  return try_lu(mat)?.try_inverse();
+}
+
+// X with A X = B. `b` may hold several right hand sides side by side.
+pub fn solve<T: Element, A: AsView<T>, B: AsView<T>>(a: &A, b: &B) -> Mat<T> {
+  return or_panic(try_solve(a, b));
+}
+
+pub fn try_solve<T: Element, A: AsView<T>, B: AsView<T>>(a: &A, b: &B) -> Result<Mat<T>, MatError> {
+  check_square("Solve", a.view())?;
+  return try_lu(a)?.try_solve(b);
+}
+
+// A = Q R with orthonormal columns in Q and an upper triangular R, built from Householder
+// reflections. For an `m x n` matrix with k = min(m, n), Q is `m x k` and R is `k x n`.
+#[derive(Clone, Debug)]
+pub struct Qr<T = f64> {
+  // R on and above the diagonal. Below it, column j holds the reflection vector w_j, whose
+  // first element is an implicit 1.
+  factors: Mat<T>,
+  // Reflection j is I - taus[j] w_j w_j^T
+  taus: Vec<T>,
+  tolerance: T,
+}
+
+impl<T: Element> Qr<T> {
+  fn reflections(&self) -> usize {
+    return self.taus.len();
+  }
+
+  // Applies reflection `j` to column `col` of `target`, whose rows line up with those of A
+  fn reflect(&self, j: usize, target: &mut Mat<T>, col: usize) {
+    let m = self.factors.rows;
+    let mut dot = target[(j, col)];
+    for i in j + 1..m {
+      dot += self.factors[(i, j)] * target[(i, col)];
+    }
+    let scaled = self.taus[j] * dot;
+    target[(j, col)] -= scaled;
+    for i in j + 1..m {
+      target[(i, col)] -= scaled * self.factors[(i, j)];
+    }
+  }
+
+  pub fn q(&self) -> Mat<T> {
+    let (m, k) = (self.factors.rows, self.reflections());
+    let mut q = Mat::zeros(m, k);
+    for j in 0..k {
+      q[(j, j)] = T::one();
+    }
+    // Q = H_0 H_1 ... H_(k-1), applied to the first k columns of the identity
+    for j in (0..k).rev() {
+      for col in 0..k {
+        self.reflect(j, &mut q, col);
+      }
+    }
+    return q;
+  }
+
+  pub fn r(&self) -> Mat<T> {
+    let (k, n) = (self.reflections(), self.factors.cols);
+    let mut r = Mat::zeros(k, n);
+    for i in 0..k {
+      for j in i..n {
+        r[(i, j)] = self.factors[(i, j)];
+      }
+    }
+    return r;
+  }
+
+  // Q^T b, as a full `m x b.cols` matrix
+  fn q_transpose_times(&self, b: MatView<T>) -> Mat<T> {
+    let mut result = b.to_mat();
+    for j in 0..self.reflections() {
+      for col in 0..result.cols {
+        self.reflect(j, &mut result, col);
+      }
+    }
+    return result;
+  }
+
+  // X minimizing ||A X - B|| column by column. Needs A with linearly independent columns,
+  // which rules out fewer rows than columns.
+  pub fn lstsq<B: AsView<T>>(&self, b: &B) -> Mat<T> {
+    return or_panic(self.try_lstsq(b));
+  }
+
+  pub fn try_lstsq<B: AsView<T>>(&self, b: &B) -> Result<Mat<T>, MatError> {
+    let b = b.view();
+    let (m, n) = (self.factors.rows, self.factors.cols);
+    if b.rows != m {
+      return Err(MatError::ShapeMismatch {
+        operation: "Least squares",
+        left: (m, n),
+        right: (b.rows, b.cols),
+      });
+    }
+    let singular = (0..self.reflections()).any(|i| self.factors[(i, i)].abs() <= self.tolerance);
+    if m < n || singular {
+      return Err(MatError::Singular {
+        operation: "Least squares",
+      });
+    }
+    // The last m - n rows of Q^T b are the residual, the first n solve R x = (Q^T b)[..n]
+    let qtb = self.q_transpose_times(b);
+    let mut x = Mat::zeros(n, b.cols);
+    for col in 0..b.cols {
+      for i in (0..n).rev() {
+        let mut value = qtb[(i, col)];
+        for j in i + 1..n {
+          value -= self.factors[(i, j)] * x[(j, col)];
+        }
+        x[(i, col)] = value / self.factors[(i, i)];
+      }
+    }
+    return Ok(x);
+  }
+}
+
+pub fn qr<T: Element, A: AsView<T>>(mat: &A) -> Qr<T> {
+  let mat = mat.view();
+  let (m, n) = (mat.rows, mat.cols);
+  let mut factors = mat.to_mat();
+  let mut taus = Vec::with_capacity(m.min(n));
+
+  for j in 0..m.min(n) {
+    // Reflect x = column j below the diagonal onto alpha e_1, with the sign of alpha chosen
+    // opposite to x_0 so that x_0 - alpha does not cancel
+    let mut norm_squared = T::zero();
+    for i in j..m {
+      norm_squared += factors[(i, j)] * factors[(i, j)];
+    }
+    let x0 = factors[(j, j)];
+    if norm_squared == T::zero() {
+      taus.push(T::zero());
+      continue;
+    }
+    let norm = norm_squared.sqrt();
+    let alpha = if x0 > T::zero() { -norm } else { norm };
+    // v = x - alpha e_1, stored as w = v / v_0 so that w_0 = 1
+    let v0 = x0 - alpha;
+    for i in j + 1..m {
+      factors[(i, j)] /= v0;
+    }
+    let tau = (alpha - x0) / alpha;
+    factors[(j, j)] = alpha;
+    taus.push(tau);
+
+    for col in j + 1..n {
+      let mut dot = factors[(j, col)];
+      for i in j + 1..m {
+        dot += factors[(i, j)] * factors[(i, col)];
+      }
+      let scaled = tau * dot;
+      factors[(j, col)] -= scaled;
+      for i in j + 1..m {
+        let w = factors[(i, j)];
+        factors[(i, col)] -= scaled * w;
+      }
+    }
+  }
+
+  return Qr {
+    factors,
+    taus,
+    tolerance: rounding_tolerance(mat),
+  };
+}
+
+// Lower triangular L with A = L L^T, for a symmetric positive definite A
+pub fn cholesky<T: Element, A: AsView<T>>(mat: &A) -> Mat<T> {
+  return or_panic(try_cholesky(mat));
+}
+
+pub fn try_cholesky<T: Element, A: AsView<T>>(mat: &A) -> Result<Mat<T>, MatError> {
+  let mat = mat.view();
+  check_symmetric("Cholesky decomposition", mat)?;
+  let n = mat.rows;
+  let mut l = Mat::zeros(n, n);
+  for j in 0..n {
+    let mut diagonal = mat[(j, j)];
+    for k in 0..j {
+      diagonal -= l[(j, k)] * l[(j, k)];
+    }
+    if diagonal <= T::zero() || diagonal.is_nan() {
+      return Err(MatError::NotPositiveDefinite {
+        operation: "Cholesky decomposition",
+      });
+    }
+    l[(j, j)] = diagonal.sqrt();
+    for i in j + 1..n {
+      let mut value = mat[(i, j)];
+      for k in 0..j {
+        value -= l[(i, k)] * l[(j, k)];
+      }
+      l[(i, j)] = value / l[(j, j)];
+    }
+  }
+  return Ok(l);
+}
+
+// X minimizing ||A X - B|| column by column, via the QR decomposition of A
+pub fn lstsq<T: Element, A: AsView<T>, B: AsView<T>>(a: &A, b: &B) -> Mat<T> {
+  return or_panic(try_lstsq(a, b));
+}
+
+pub fn try_lstsq<T: Element, A: AsView<T>, B: AsView<T>>(a: &A, b: &B) -> Result<Mat<T>, MatError> {
+  return qr(a).try_lstsq(b);
+}
@@ -0,0 +1,272 @@
+// Operator overloads for `Mat`. Binary operators work on any mix of owned matrices and
+// references; `*` between two matrices is the matrix product. With a scalar on the right, `*`
+// and `/` scale every element and `+` and `-` shift it.
+use super::{
+  add_scalar, addition, broadcast_shape, check_in_bounds, dot_product, or_panic, scale,
+  subtraction, AsView, Element, Mat, MatError, MatView, MatViewMut,
+};
+use std::ops::{
+  Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+macro_rules! impl_binary_op {
+  ($trait:ident, $method:ident, $function:ident) => {
+    impl<T: Element> $trait<Mat<T>> for Mat<T> {
+      type Output = Mat<T>;
+      fn $method(self, rhs: Mat<T>) -> Mat<T> {
+        return $function(&self, &rhs);
+      }
+    }
+
+    impl<T: Element> $trait<&Mat<T>> for Mat<T> {
+      type Output = Mat<T>;
+      fn $method(self, rhs: &Mat<T>) -> Mat<T> {
+        return $function(&self, rhs);
+      }
+    }
+
+    impl<T: Element> $trait<Mat<T>> for &Mat<T> {
+      type Output = Mat<T>;
+      fn $method(self, rhs: Mat<T>) -> Mat<T> {
+        return $function(self, &rhs);
+      }
+    }
+
+    impl<T: Element> $trait<&Mat<T>> for &Mat<T> {
+      type Output = Mat<T>;
+      fn $method(self, rhs: &Mat<T>) -> Mat<T> {
+        return $function(self, rhs);
+      }
+    }
+  };
+}
+
+impl_binary_op!(Add, add, addition);
+impl_binary_op!(Sub, sub, subtraction);
+impl_binary_op!(Mul, mul, dot_product);
+
+// Applies `f` to every element of `mat` together with the matching element of `other`, which
+// may broadcast to the shape of `mat` but not grow it
+fn zip_in_place<T: Element>(
+  mat: &mut Mat<T>,
+  other: &Mat<T>,
+  operation: &'static str,
+  f: impl Fn(T, T) -> T,
+) {
+  let shape = or_panic(broadcast_shape(operation, mat.view(), other.view()));
+  if shape != (mat.rows, mat.cols) {
+    panic!(
+      "{}",
+      MatError::ShapeMismatch {
+        operation,
+        left: (mat.rows, mat.cols),
+        right: (other.rows, other.cols),
+      }
+    );
+  }
+  for i in 0..mat.rows {
+    for j in 0..mat.cols {
+      // `other` has either one row or as many as `mat`, so the remainder picks the right one
+      let (row, col) = (i % other.rows, j % other.cols);
+      mat[(i, j)] = f(mat[(i, j)], other[(row, col)]);
+    }
+  }
+}
+
+impl<T: Element> AddAssign<&Mat<T>> for Mat<T> {
+  fn add_assign(&mut self, rhs: &Mat<T>) {
+    zip_in_place(self, rhs, "Addition", |a, b| a + b);
+  }
+}
+
+impl<T: Element> AddAssign<Mat<T>> for Mat<T> {
+  fn add_assign(&mut self, rhs: Mat<T>) {
+    *self += &rhs;
+  }
+}
+
+impl<T: Element> SubAssign<&Mat<T>> for Mat<T> {
+  fn sub_assign(&mut self, rhs: &Mat<T>) {
+    zip_in_place(self, rhs, "Subtraction", |a, b| a - b);
+  }
+}
+
+impl<T: Element> SubAssign<Mat<T>> for Mat<T> {
+  fn sub_assign(&mut self, rhs: Mat<T>) {
+    *self -= &rhs;
+  }
+}
+
+// `a *= b` replaces `a` with the matrix product `a * b`, which may change its shape
+impl<T: Element> MulAssign<&Mat<T>> for Mat<T> {
+  fn mul_assign(&mut self, rhs: &Mat<T>) {
+    *self = dot_product(self, rhs);
+  }
+}
+
+impl<T: Element> MulAssign<Mat<T>> for Mat<T> {
+  fn mul_assign(&mut self, rhs: Mat<T>) {
+    *self *= &rhs;
+  }
+}
+
+impl<T: Element> MulAssign<T> for Mat<T> {
+  fn mul_assign(&mut self, rhs: T) {
+    self.map_inplace(|value| value * rhs);
+  }
+}
+
+// `mat + scalar` and `mat - scalar` shift every element
+impl<T: Element> AddAssign<T> for Mat<T> {
+  fn add_assign(&mut self, rhs: T) {
+    self.map_inplace(|value| value + rhs);
+  }
+}
+
+impl<T: Element> SubAssign<T> for Mat<T> {
+  fn sub_assign(&mut self, rhs: T) {
+    self.map_inplace(|value| value - rhs);
+  }
+}
+
+impl<T: Element> Add<T> for Mat<T> {
+  type Output = Mat<T>;
+  fn add(mut self, rhs: T) -> Mat<T> {
+    self += rhs;
+    return self;
+  }
+}
+
+impl<T: Element> Add<T> for &Mat<T> {
+  type Output = Mat<T>;
+  fn add(self, rhs: T) -> Mat<T> {
+    return add_scalar(self, rhs);
+  }
+}
+
+impl<T: Element> Sub<T> for Mat<T> {
+  type Output = Mat<T>;
+  fn sub(mut self, rhs: T) -> Mat<T> {
+    self -= rhs;
+    return self;
+  }
+}
+
+impl<T: Element> Sub<T> for &Mat<T> {
+  type Output = Mat<T>;
+  fn sub(self, rhs: T) -> Mat<T> {
+    return add_scalar(self, -rhs);
+  }
+}
+
+impl<T: Element> DivAssign<T> for Mat<T> {
+  fn div_assign(&mut self, rhs: T) {
+    self.map_inplace(|value| value / rhs);
+  }
+}
+
+impl<T: Element> Mul<T> for Mat<T> {
+  type Output = Mat<T>;
+  fn mul(mut self, rhs: T) -> Mat<T> {
+    self *= rhs;
+    return self;
+  }
+}
+
+impl<T: Element> Mul<T> for &Mat<T> {
+  type Output = Mat<T>;
+  fn mul(self, rhs: T) -> Mat<T> {
+    return scale(self, rhs);
+  }
+}
+
+// `scalar * mat`. The scalar is the receiver here, so every element type needs its own impl.
+macro_rules! impl_scalar_times_mat {
+  ($($type:ty),*) => {
+    $(
+      impl Mul<Mat<$type>> for $type {
+        type Output = Mat<$type>;
+        fn mul(self, rhs: Mat<$type>) -> Mat<$type> {
+          return rhs * self;
+        }
+      }
+
+      impl Mul<&Mat<$type>> for $type {
+        type Output = Mat<$type>;
+        fn mul(self, rhs: &Mat<$type>) -> Mat<$type> {
+          return rhs * self;
+        }
+      }
+    )*
+  };
+}
+
+impl_scalar_times_mat!(f32, f64);
+
+impl<T: Element> Div<T> for Mat<T> {
+  type Output = Mat<T>;
+  fn div(mut self, rhs: T) -> Mat<T> {
+    self /= rhs;
+    return self;
+  }
+}
+
+impl<T: Element> Div<T> for &Mat<T> {
+  type Output = Mat<T>;
+  fn div(self, rhs: T) -> Mat<T> {
+    return self.clone() / rhs;
+  }
+}
+
+impl<T: Element> Neg for Mat<T> {
+  type Output = Mat<T>;
+  fn neg(mut self) -> Mat<T> {
+    self.map_inplace(|value| -value);
+    return self;
+  }
+}
+
+impl<T: Element> Neg for &Mat<T> {
+  type Output = Mat<T>;
+  fn neg(self) -> Mat<T> {
+    return -self.clone();
+  }
+}
+
+impl<T> Index<(usize, usize)> for Mat<T> {
+  type Output = T;
+  fn index(&self, (row, col): (usize, usize)) -> &T {
+    or_panic(check_in_bounds("Index", row, col, self.rows, self.cols));
+    return &self.data_stream[row * self.stride + col];
+  }
+}
+
+impl<T> IndexMut<(usize, usize)> for Mat<T> {
+  fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+    or_panic(check_in_bounds("Index", row, col, self.rows, self.cols));
+    return &mut self.data_stream[row * self.stride + col];
+  }
+}
+
+impl<T> Index<(usize, usize)> for MatView<'_, T> {
+  type Output = T;
+  fn index(&self, (row, col): (usize, usize)) -> &T {
+    or_panic(check_in_bounds("Index", row, col, self.rows, self.cols));
+    return &self.data_stream[row * self.stride + col];
+  }
+}
+
+impl<T> Index<(usize, usize)> for MatViewMut<'_, T> {
+  type Output = T;
+  fn index(&self, (row, col): (usize, usize)) -> &T {
+    or_panic(check_in_bounds("Index", row, col, self.rows, self.cols));
+    return &self.data_stream[row * self.stride + col];
+  }
+}
+
+impl<T> IndexMut<(usize, usize)> for MatViewMut<'_, T> {
+  fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+    or_panic(check_in_bounds("Index", row, col, self.rows, self.cols));
+    return &mut self.data_stream[row * self.stride + col];
+  }
+}
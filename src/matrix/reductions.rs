@@ -0,0 +1,241 @@
+// Reductions over all elements of a matrix or along one axis: sums, means, spread, extremes
+// and norms. Matrices always have at least one element, so none of them can be empty.
+use super::{svd, AsView, Element, Mat, MatView};
+
+// Which values a `*_axis` reduction combines. `Row` reduces every row to one value and gives a
+// `rows x 1` column, `Column` reduces every column and gives a `1 x cols` row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+  Row,
+  Column,
+}
+
+// Matrix norms. `L1`, `L2` and `Infinity` are the norms induced by the vector norms of the same
+// name, so on a column they are the usual vector norms. `Frobenius`, `EntrywiseL1` and `Max`
+// read the matrix as one long vector of its elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Norm {
+  // Largest sum of absolute values in a column
+  L1,
+  // Spectral norm, the largest singular value
+  L2,
+  // Square root of the sum of squares
+  Frobenius,
+  // Largest sum of absolute values in a row
+  Infinity,
+  // Sum of the absolute values of all elements
+  EntrywiseL1,
+  // Largest absolute value
+  Max,
+}
+
+impl<'a, T: Element> MatView<'a, T> {
+  // The rows of the view without the gaps between them
+  fn row_slices(&self) -> impl Iterator<Item = &'a [T]> {
+    let cols = self.cols;
+    return self
+      .data_stream
+      .chunks(self.stride)
+      .take(self.rows)
+      .map(move |row| &row[..cols]);
+  }
+
+  fn count(&self) -> T {
+    return T::constant((self.rows * self.cols) as f64);
+  }
+
+  pub fn sum(&self) -> T {
+    return self
+      .row_slices()
+      .fold(T::zero(), |sum, row| sum + T::sum_row(row));
+  }
+
+  pub fn sum_axis(&self, axis: Axis) -> Mat<T> {
+    return match axis {
+      Axis::Row => {
+        let sums = self.row_slices().map(T::sum_row).collect();
+        Mat::from_vec(self.rows, 1, sums)
+      }
+      Axis::Column => {
+        let mut sums = vec![T::zero(); self.cols];
+        for row in self.row_slices() {
+          for (sum, &value) in sums.iter_mut().zip(row) {
+            *sum += value;
+          }
+        }
+        Mat::from_vec(1, self.cols, sums)
+      }
+    };
+  }
+
+  pub fn mean(&self) -> T {
+    return self.sum() / self.count();
+  }
+
+  pub fn mean_axis(&self, axis: Axis) -> Mat<T> {
+    let count = match axis {
+      Axis::Row => self.cols,
+      Axis::Column => self.rows,
+    };
+    let mut means = self.sum_axis(axis);
+    means /= T::constant(count as f64);
+    return means;
+  }
+
+  // Population variance, i.e. the mean squared distance from the mean
+  pub fn var(&self) -> T {
+    let mean = self.mean();
+    let squares = self.row_slices().flatten().fold(T::zero(), |sum, &value| {
+      let deviation = value - mean;
+      return sum + deviation * deviation;
+    });
+    return squares / self.count();
+  }
+
+  // Population standard deviation
+  pub fn std(&self) -> T {
+    return self.var().sqrt();
+  }
+
+  // Smallest element. NaN is skipped unless every element is NaN.
+  pub fn min(&self) -> T {
+    return self
+      .row_slices()
+      .flatten()
+      .fold(T::nan(), |min, &value| min.min(value));
+  }
+
+  // Largest element. NaN is skipped unless every element is NaN.
+  pub fn max(&self) -> T {
+    return self
+      .row_slices()
+      .flatten()
+      .fold(T::nan(), |max, &value| max.max(value));
+  }
+
+  // Smallest element of every row or column, shaped like `sum_axis`. NaN is skipped as in `min`.
+  pub fn min_axis(&self, axis: Axis) -> Mat<T> {
+    return self.fold_axis(axis, T::nan(), |min, value| min.min(value));
+  }
+
+  // Largest element of every row or column, shaped like `sum_axis`
+  pub fn max_axis(&self, axis: Axis) -> Mat<T> {
+    return self.fold_axis(axis, T::nan(), |max, value| max.max(value));
+  }
+
+  // Folds every row (`Axis::Row`) or column (`Axis::Column`) into one value starting at `init`
+  fn fold_axis(&self, axis: Axis, init: T, f: impl Fn(T, T) -> T) -> Mat<T> {
+    return match axis {
+      Axis::Row => {
+        let values = self
+          .row_slices()
+          .map(|row| row.iter().fold(init, |acc, &value| f(acc, value)))
+          .collect();
+        Mat::from_vec(self.rows, 1, values)
+      }
+      Axis::Column => {
+        let mut values = vec![init; self.cols];
+        for row in self.row_slices() {
+          for (acc, &value) in values.iter_mut().zip(row) {
+            *acc = f(*acc, value);
+          }
+        }
+        Mat::from_vec(1, self.cols, values)
+      }
+    };
+  }
+
+  // Index of the largest element of every row (`Axis::Row`) or column (`Axis::Column`). Ties
+  // go to the first index, e.g. the predicted class of every row of a batch of outputs.
+  pub fn argmax_axis(&self, axis: Axis) -> Vec<usize> {
+    return match axis {
+      Axis::Row => self.row_slices().map(argmax).collect(),
+      Axis::Column => {
+        let mut best = vec![0; self.cols];
+        for (i, row) in self.row_slices().enumerate().skip(1) {
+          for (j, &value) in row.iter().enumerate() {
+            if value > self[(best[j], j)] {
+              best[j] = i;
+            }
+          }
+        }
+        best
+      }
+    };
+  }
+
+  pub fn norm(&self, norm: Norm) -> T {
+    let elements = self.row_slices().flatten();
+    let abs_sum = |sum: T, value: T| sum + value.abs();
+    return match norm {
+      Norm::L1 => self.fold_axis(Axis::Column, T::zero(), abs_sum).max(),
+      Norm::L2 => svd(self).singular_values[0],
+      Norm::Infinity => self.fold_axis(Axis::Row, T::zero(), abs_sum).max(),
+      Norm::EntrywiseL1 => elements.fold(T::zero(), |sum, &value| abs_sum(sum, value)),
+      Norm::Frobenius => elements
+        .fold(T::zero(), |sum, &value| sum + value * value)
+        .sqrt(),
+      Norm::Max => elements.fold(T::zero(), |max, &value| max.max(value.abs())),
+    };
+  }
+}
+
+fn argmax<T: Element>(values: &[T]) -> usize {
+  let mut best = 0;
+  for (i, &value) in values.iter().enumerate() {
+    if value > values[best] {
+      best = i;
+    }
+  }
+  return best;
+}
+
+impl<T: Element> Mat<T> {
+  pub fn sum(&self) -> T {
+    return self.view().sum();
+  }
+
+  pub fn sum_axis(&self, axis: Axis) -> Mat<T> {
+    return self.view().sum_axis(axis);
+  }
+
+  pub fn mean(&self) -> T {
+    return self.view().mean();
+  }
+
+  pub fn mean_axis(&self, axis: Axis) -> Mat<T> {
+    return self.view().mean_axis(axis);
+  }
+
+  pub fn var(&self) -> T {
+    return self.view().var();
+  }
+
+  pub fn std(&self) -> T {
+    return self.view().std();
+  }
+
+  pub fn min(&self) -> T {
+    return self.view().min();
+  }
+
+  pub fn max(&self) -> T {
+    return self.view().max();
+  }
+
+  pub fn min_axis(&self, axis: Axis) -> Mat<T> {
+    return self.view().min_axis(axis);
+  }
+
+  pub fn max_axis(&self, axis: Axis) -> Mat<T> {
+    return self.view().max_axis(axis);
+  }
+
+  pub fn argmax_axis(&self, axis: Axis) -> Vec<usize> {
+    return self.view().argmax_axis(axis);
+  }
+
+  pub fn norm(&self, norm: Norm) -> T {
+    return self.view().norm(norm);
+  }
+}
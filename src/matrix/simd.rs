@@ -0,0 +1,324 @@
+// Vectorized loops over contiguous runs of f64, such as one row of a matrix. On x86_64 the
+// widest instruction set the CPU supports is picked at runtime (AVX2, then SSE2, which every
+// x86_64 CPU has); everywhere else the plain scalar loops run.
+//
+// Elementwise kernels give bitwise the same results as the scalar loops. The reductions add in
+// a different order, so they can differ from them in the last few bits.
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SimdLevel {
+  Scalar,
+  Sse2,
+  Avx2,
+}
+
+// Widest level the running CPU supports, looked up once
+pub fn detect() -> SimdLevel {
+  static LEVEL: OnceLock<SimdLevel> = OnceLock::new();
+  return *LEVEL.get_or_init(|| {
+    #[cfg(target_arch = "x86_64")]
+    {
+      if is_x86_feature_detected!("avx2") {
+        return SimdLevel::Avx2;
+      }
+      return SimdLevel::Sse2;
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    return SimdLevel::Scalar;
+  });
+}
+
+// Every level the running CPU can execute, scalar first
+pub fn supported_levels() -> Vec<SimdLevel> {
+  return [SimdLevel::Scalar, SimdLevel::Sse2, SimdLevel::Avx2]
+    .into_iter()
+    .filter(|level| *level <= detect())
+    .collect();
+}
+
+fn check_level(level: SimdLevel) {
+  if level > detect() {
+    panic!("{:?} is not supported on this CPU", level);
+  }
+}
+
+fn check_lengths(a: &[f64], b: &[f64], out: &[f64]) {
+  if a.len() != out.len() || b.len() != out.len() {
+    panic!(
+      "Slice lengths must match. Got a: {}, b: {} and out: {}",
+      a.len(),
+      b.len(),
+      out.len()
+    );
+  }
+}
+
+// Same check for the reductions, which have no output slice
+fn check_pair(a: &[f64], b: &[f64]) {
+  if a.len() != b.len() {
+    panic!(
+      "Slice lengths must match. Got a: {} and b: {}",
+      a.len(),
+      b.len()
+    );
+  }
+}
+
+// Picks the implementation for `level`. Only called after `check_level`, so the CPU is known
+// to support the instructions the unsafe functions use.
+macro_rules! dispatch {
+  ($level:expr, $scalar:expr, $sse2:expr, $avx2:expr) => {
+    match $level {
+      #[cfg(target_arch = "x86_64")]
+      SimdLevel::Avx2 => unsafe { $avx2 },
+      #[cfg(target_arch = "x86_64")]
+      SimdLevel::Sse2 => unsafe { $sse2 },
+      _ => $scalar,
+    }
+  };
+}
+
+macro_rules! binary_op {
+  ($name:ident, $name_with:ident, $op:tt) => {
+    // out = a $op b, element by element
+    pub fn $name(a: &[f64], b: &[f64], out: &mut [f64]) {
+      $name_with(detect(), a, b, out);
+    }
+
+    pub fn $name_with(level: SimdLevel, a: &[f64], b: &[f64], out: &mut [f64]) {
+      check_level(level);
+      check_lengths(a, b, out);
+      dispatch!(
+        level,
+        scalar::$name(a, b, out),
+        x86::sse2::$name(a, b, out),
+        x86::avx2::$name(a, b, out)
+      );
+    }
+  };
+}
+
+binary_op!(add, add_with, +);
+binary_op!(sub, sub_with, -);
+binary_op!(mul, mul_with, *);
+binary_op!(div, div_with, /);
+
+// Sets every element of `out` to `value`
+pub fn fill(out: &mut [f64], value: f64) {
+  fill_with(detect(), out, value);
+}
+
+pub fn fill_with(level: SimdLevel, out: &mut [f64], value: f64) {
+  check_level(level);
+  dispatch!(
+    level,
+    scalar::fill(out, value),
+    x86::sse2::fill(out, value),
+    x86::avx2::fill(out, value)
+  );
+}
+
+// Replaces every element x of `values` by max(x, 0), the same as `Activation::Relu`
+pub fn relu(values: &mut [f64]) {
+  relu_with(detect(), values);
+}
+
+pub fn relu_with(level: SimdLevel, values: &mut [f64]) {
+  check_level(level);
+  dispatch!(
+    level,
+    scalar::relu(values),
+    x86::sse2::relu(values),
+    x86::avx2::relu(values)
+  );
+}
+
+pub fn sum(values: &[f64]) -> f64 {
+  return sum_with(detect(), values);
+}
+
+pub fn sum_with(level: SimdLevel, values: &[f64]) -> f64 {
+  check_level(level);
+  return dispatch!(
+    level,
+    scalar::sum(values),
+    x86::sse2::sum(values),
+    x86::avx2::sum(values)
+  );
+}
+
+pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+  return dot_with(detect(), a, b);
+}
+
+pub fn dot_with(level: SimdLevel, a: &[f64], b: &[f64]) -> f64 {
+  check_level(level);
+  check_pair(a, b);
+  return dispatch!(
+    level,
+    scalar::dot(a, b),
+    x86::sse2::dot(a, b),
+    x86::avx2::dot(a, b)
+  );
+}
+
+mod scalar {
+  macro_rules! binary_op {
+    ($name:ident, $op:tt) => {
+      pub fn $name(a: &[f64], b: &[f64], out: &mut [f64]) {
+        for ((value, &x), &y) in out.iter_mut().zip(a).zip(b) {
+          *value = x $op y;
+        }
+      }
+    };
+  }
+
+  binary_op!(add, +);
+  binary_op!(sub, -);
+  binary_op!(mul, *);
+  binary_op!(div, /);
+
+  pub fn fill(out: &mut [f64], value: f64) {
+    for element in out.iter_mut() {
+      *element = value;
+    }
+  }
+
+  pub fn relu(values: &mut [f64]) {
+    for value in values.iter_mut() {
+      *value = value.max(0.0);
+    }
+  }
+
+  pub fn sum(values: &[f64]) -> f64 {
+    let mut sum = 0.0;
+    for &value in values {
+      sum += value;
+    }
+    return sum;
+  }
+
+  pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+    let mut sum = 0.0;
+    for (&x, &y) in a.iter().zip(b) {
+      sum += x * y;
+    }
+    return sum;
+  }
+}
+
+// Both instruction sets share one implementation, generated for the register type, lane
+// count and intrinsics of each. Loads and stores are unaligned, so any slice works; whatever
+// does not fill a whole register is handled by the scalar loops.
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+  macro_rules! kernels {
+    (
+      $module:ident, $feature:literal, $lanes:expr,
+      $loadu:ident, $storeu:ident, $set1:ident, $setzero:ident,
+      $add:ident, $sub:ident, $mul:ident, $div:ident, $max:ident
+    ) => {
+      pub mod $module {
+        use super::super::scalar;
+        use std::arch::x86_64::*;
+
+        const LANES: usize = $lanes;
+
+        macro_rules! binary_op {
+          ($name:ident, $intrinsic:ident) => {
+            #[target_feature(enable = $feature)]
+            pub unsafe fn $name(a: &[f64], b: &[f64], out: &mut [f64]) {
+              let whole = out.len() - out.len() % LANES;
+              for i in (0..whole).step_by(LANES) {
+                let x = $loadu(a.as_ptr().add(i));
+                let y = $loadu(b.as_ptr().add(i));
+                $storeu(out.as_mut_ptr().add(i), $intrinsic(x, y));
+              }
+              scalar::$name(&a[whole..], &b[whole..], &mut out[whole..]);
+            }
+          };
+        }
+
+        binary_op!(add, $add);
+        binary_op!(sub, $sub);
+        binary_op!(mul, $mul);
+        binary_op!(div, $div);
+
+        #[target_feature(enable = $feature)]
+        pub unsafe fn fill(out: &mut [f64], value: f64) {
+          let whole = out.len() - out.len() % LANES;
+          let register = $set1(value);
+          for i in (0..whole).step_by(LANES) {
+            $storeu(out.as_mut_ptr().add(i), register);
+          }
+          scalar::fill(&mut out[whole..], value);
+        }
+
+        // max returns its second operand for NaN, which maps NaN to 0 like f64::max does
+        #[target_feature(enable = $feature)]
+        pub unsafe fn relu(values: &mut [f64]) {
+          let whole = values.len() - values.len() % LANES;
+          let zero = $setzero();
+          for i in (0..whole).step_by(LANES) {
+            let x = $loadu(values.as_ptr().add(i));
+            $storeu(values.as_mut_ptr().add(i), $max(x, zero));
+          }
+          scalar::relu(&mut values[whole..]);
+        }
+
+        #[target_feature(enable = $feature)]
+        pub unsafe fn sum(values: &[f64]) -> f64 {
+          let whole = values.len() - values.len() % LANES;
+          let mut sums = $setzero();
+          for i in (0..whole).step_by(LANES) {
+            sums = $add(sums, $loadu(values.as_ptr().add(i)));
+          }
+          let lanes: [f64; LANES] = std::mem::transmute(sums);
+          return scalar::sum(&lanes) + scalar::sum(&values[whole..]);
+        }
+
+        #[target_feature(enable = $feature)]
+        pub unsafe fn dot(a: &[f64], b: &[f64]) -> f64 {
+          let whole = a.len() - a.len() % LANES;
+          let mut sums = $setzero();
+          for i in (0..whole).step_by(LANES) {
+            let product = $mul($loadu(a.as_ptr().add(i)), $loadu(b.as_ptr().add(i)));
+            sums = $add(sums, product);
+          }
+          let lanes: [f64; LANES] = std::mem::transmute(sums);
+          return scalar::sum(&lanes) + scalar::dot(&a[whole..], &b[whole..]);
+        }
+      }
+    };
+  }
+
+  kernels!(
+    sse2,
+    "sse2",
+    2,
+    _mm_loadu_pd,
+    _mm_storeu_pd,
+    _mm_set1_pd,
+    _mm_setzero_pd,
+    _mm_add_pd,
+    _mm_sub_pd,
+    _mm_mul_pd,
+    _mm_div_pd,
+    _mm_max_pd
+  );
+  kernels!(
+    avx2,
+    "avx2",
+    4,
+    _mm256_loadu_pd,
+    _mm256_storeu_pd,
+    _mm256_set1_pd,
+    _mm256_setzero_pd,
+    _mm256_add_pd,
+    _mm256_sub_pd,
+    _mm256_mul_pd,
+    _mm256_div_pd,
+    _mm256_max_pd
+  );
+}
@@ -0,0 +1,313 @@
+// Views onto parts of a matrix: columns, rectangular blocks, reshaped and transposed windows.
+// Every view shares the buffer it was taken from; `stride` is what lets a block or a column
+// skip the elements of the parent that lie outside of it.
+use super::{
+  check_dimensions, check_in_bounds, or_panic, AsView, AsViewMut, Element, Mat, MatError, MatView,
+  MatViewMut,
+};
+use std::ops::{Bound, Index, RangeBounds};
+
+// Resolves `range` against a dimension of `len` into a half-open `(start, end)`. A bound one
+// past `usize::MAX`, e.g. `..=usize::MAX`, is an overflow.
+fn resolve(range: impl RangeBounds<usize>, len: usize) -> Result<(usize, usize), MatError> {
+  let start = match range.start_bound() {
+    Bound::Included(&start) => start,
+    Bound::Excluded(&start) => start.checked_add(1).ok_or(MatError::Overflow)?,
+    Bound::Unbounded => 0,
+  };
+  let end = match range.end_bound() {
+    Bound::Included(&end) => end.checked_add(1).ok_or(MatError::Overflow)?,
+    Bound::Excluded(&end) => end,
+    Bound::Unbounded => len,
+  };
+  return Ok((start, end));
+}
+
+// Offset of the `rows x cols` block at (`row`, `col`) in a buffer with `stride`, and the number
+// of elements the block spans from there
+fn block_extent(
+  operation: &'static str,
+  (rows, cols, stride): (usize, usize, usize),
+  (row, col): (usize, usize),
+  (block_rows, block_cols): (usize, usize),
+) -> Result<(usize, usize), MatError> {
+  check_dimensions(block_rows, block_cols)?;
+  // The last row and column of the block must lie inside the matrix
+  let last_row = row.checked_add(block_rows - 1).ok_or(MatError::Overflow)?;
+  let last_col = col.checked_add(block_cols - 1).ok_or(MatError::Overflow)?;
+  check_in_bounds(operation, last_row, last_col, rows, cols)?;
+  let start = row * stride + col;
+  return Ok((start, (block_rows - 1) * stride + block_cols));
+}
+
+impl<'a, T: Element> MatView<'a, T> {
+  // Column `j` as a `rows x 1` view
+  pub fn col(self, j: usize) -> MatView<'a, T> {
+    return or_panic(self.try_col(j));
+  }
+
+  pub fn try_col(self, j: usize) -> Result<MatView<'a, T>, MatError> {
+    check_in_bounds("Column", 0, j, self.rows, self.cols)?;
+    return self.try_submatrix(0, j, self.rows, 1);
+  }
+
+  // The `rows x cols` block whose top left element is (`row`, `col`)
+  pub fn submatrix(self, row: usize, col: usize, rows: usize, cols: usize) -> MatView<'a, T> {
+    return or_panic(self.try_submatrix(row, col, rows, cols));
+  }
+
+  pub fn try_submatrix(
+    self,
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+  ) -> Result<MatView<'a, T>, MatError> {
+    let shape = (self.rows, self.cols, self.stride);
+    let (start, len) = block_extent("Submatrix", shape, (row, col), (rows, cols))?;
+    return Ok(MatView {
+      rows,
+      cols,
+      stride: self.stride,
+      data_stream: &self.data_stream[start..start + len],
+    });
+  }
+
+  // The block covering `row_range` x `col_range`, e.g. `slice(1..3, ..)` for rows 1 and 2
+  pub fn slice(
+    self,
+    row_range: impl RangeBounds<usize>,
+    col_range: impl RangeBounds<usize>,
+  ) -> MatView<'a, T> {
+    return or_panic(self.try_slice(row_range, col_range));
+  }
+
+  pub fn try_slice(
+    self,
+    row_range: impl RangeBounds<usize>,
+    col_range: impl RangeBounds<usize>,
+  ) -> Result<MatView<'a, T>, MatError> {
+    let (row_start, row_end) = resolve(row_range, self.rows)?;
+    let (col_start, col_end) = resolve(col_range, self.cols)?;
+    return self.try_submatrix(
+      row_start,
+      col_start,
+      row_end.saturating_sub(row_start),
+      col_end.saturating_sub(col_start),
+    );
+  }
+
+  // The same elements read row by row into `rows x cols`. Only possible when the rows of the
+  // view follow each other without gaps.
+  pub fn reshape(self, rows: usize, cols: usize) -> MatView<'a, T> {
+    return or_panic(self.try_reshape(rows, cols));
+  }
+
+  pub fn try_reshape(self, rows: usize, cols: usize) -> Result<MatView<'a, T>, MatError> {
+    let len = self.rows * self.cols;
+    if check_dimensions(rows, cols)? != len {
+      return Err(MatError::LengthMismatch { rows, cols, len });
+    }
+    if self.rows > 1 && self.stride != self.cols {
+      return Err(MatError::NotContiguous {
+        rows: self.rows,
+        cols: self.cols,
+        stride: self.stride,
+      });
+    }
+    return Ok(MatView {
+      rows,
+      cols,
+      stride: cols,
+      data_stream: &self.data_stream[..len],
+    });
+  }
+
+  // Reads this view as its transpose without copying
+  pub fn t(self) -> TransposedView<'a, T> {
+    return TransposedView {
+      rows: self.cols,
+      cols: self.rows,
+      inner: self,
+    };
+  }
+
+  // Copies the transpose into a new matrix
+  pub fn transpose(self) -> Mat<T> {
+    return self.t().to_mat();
+  }
+}
+
+impl<'a, T: Element> MatViewMut<'a, T> {
+  // Mutable counterpart of `MatView::submatrix`
+  pub fn submatrix_mut(
+    self,
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+  ) -> MatViewMut<'a, T> {
+    return or_panic(self.try_submatrix_mut(row, col, rows, cols));
+  }
+
+  pub fn try_submatrix_mut(
+    self,
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+  ) -> Result<MatViewMut<'a, T>, MatError> {
+    let shape = (self.rows, self.cols, self.stride);
+    let (start, len) = block_extent("Submatrix", shape, (row, col), (rows, cols))?;
+    return Ok(MatViewMut {
+      rows,
+      cols,
+      stride: self.stride,
+      data_stream: &mut self.data_stream[start..start + len],
+    });
+  }
+
+  // Mutable counterpart of `MatView::slice`
+  pub fn slice_mut(
+    self,
+    row_range: impl RangeBounds<usize>,
+    col_range: impl RangeBounds<usize>,
+  ) -> MatViewMut<'a, T> {
+    return or_panic(self.try_slice_mut(row_range, col_range));
+  }
+
+  pub fn try_slice_mut(
+    self,
+    row_range: impl RangeBounds<usize>,
+    col_range: impl RangeBounds<usize>,
+  ) -> Result<MatViewMut<'a, T>, MatError> {
+    let (row_start, row_end) = resolve(row_range, self.rows)?;
+    let (col_start, col_end) = resolve(col_range, self.cols)?;
+    return self.try_submatrix_mut(
+      row_start,
+      col_start,
+      row_end.saturating_sub(row_start),
+      col_end.saturating_sub(col_start),
+    );
+  }
+}
+
+// The same views taken straight from an owned matrix
+impl<T: Element> Mat<T> {
+  pub fn col(&self, j: usize) -> MatView<'_, T> {
+    return self.view().col(j);
+  }
+
+  pub fn try_col(&self, j: usize) -> Result<MatView<'_, T>, MatError> {
+    return self.view().try_col(j);
+  }
+
+  pub fn submatrix(&self, row: usize, col: usize, rows: usize, cols: usize) -> MatView<'_, T> {
+    return self.view().submatrix(row, col, rows, cols);
+  }
+
+  pub fn try_submatrix(
+    &self,
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+  ) -> Result<MatView<'_, T>, MatError> {
+    return self.view().try_submatrix(row, col, rows, cols);
+  }
+
+  pub fn submatrix_mut(
+    &mut self,
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+  ) -> MatViewMut<'_, T> {
+    return self.view_mut().submatrix_mut(row, col, rows, cols);
+  }
+
+  pub fn slice(
+    &self,
+    row_range: impl RangeBounds<usize>,
+    col_range: impl RangeBounds<usize>,
+  ) -> MatView<'_, T> {
+    return self.view().slice(row_range, col_range);
+  }
+
+  pub fn try_slice(
+    &self,
+    row_range: impl RangeBounds<usize>,
+    col_range: impl RangeBounds<usize>,
+  ) -> Result<MatView<'_, T>, MatError> {
+    return self.view().try_slice(row_range, col_range);
+  }
+
+  pub fn slice_mut(
+    &mut self,
+    row_range: impl RangeBounds<usize>,
+    col_range: impl RangeBounds<usize>,
+  ) -> MatViewMut<'_, T> {
+    return self.view_mut().slice_mut(row_range, col_range);
+  }
+
+  pub fn reshape(&self, rows: usize, cols: usize) -> MatView<'_, T> {
+    return self.view().reshape(rows, cols);
+  }
+
+  pub fn try_reshape(&self, rows: usize, cols: usize) -> Result<MatView<'_, T>, MatError> {
+    return self.view().try_reshape(rows, cols);
+  }
+
+  pub fn t(&self) -> TransposedView<'_, T> {
+    return self.view().t();
+  }
+
+  pub fn transpose(&self) -> Mat<T> {
+    return self.view().transpose();
+  }
+}
+
+// A matrix read as its transpose: element (i, j) is element (j, i) of the matrix it was taken
+// from. Its columns are not contiguous, so it is no `AsView` and the matrix functions do not
+// take it: pass the original to `dot_product_transposed` to multiply by it without copying, or
+// copy it out with `to_mat()` for anything else.
+#[derive(Clone, Copy, Debug)]
+pub struct TransposedView<'a, T = f64> {
+  pub rows: usize,
+  pub cols: usize,
+  inner: MatView<'a, T>,
+}
+
+impl<'a, T: Element> TransposedView<'a, T> {
+  pub fn get(&self, row: usize, col: usize) -> Option<T> {
+    return self.try_get(row, col).ok();
+  }
+
+  pub fn try_get(&self, row: usize, col: usize) -> Result<T, MatError> {
+    check_in_bounds("Get", row, col, self.rows, self.cols)?;
+    return self.inner.try_get(col, row);
+  }
+
+  // The untransposed view this was taken from
+  pub fn t(self) -> MatView<'a, T> {
+    return self.inner;
+  }
+
+  pub fn to_mat(&self) -> Mat<T> {
+    let mut result = Mat::zeros(self.rows, self.cols);
+    for i in 0..self.rows {
+      for j in 0..self.cols {
+        result[(i, j)] = self.inner[(j, i)];
+      }
+    }
+    return result;
+  }
+}
+
+impl<T> Index<(usize, usize)> for TransposedView<'_, T> {
+  type Output = T;
+  fn index(&self, (row, col): (usize, usize)) -> &T {
+    or_panic(check_in_bounds("Index", row, col, self.rows, self.cols));
+    return &self.inner[(col, row)];
+  }
+}
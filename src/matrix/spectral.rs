@@ -0,0 +1,322 @@
+// Spectral decompositions: eigenvalues of symmetric matrices and the singular value
+// decomposition, both by Jacobi rotations, and principal component analysis built on top.
+use super::linalg::check_symmetric;
+use super::{
+  dot_product, or_panic, subtraction, AsView, Axis, Element, Mat, MatError, MatView, Norm,
+};
+
+// Jacobi rotations converge quadratically, so real matrices settle within a handful of sweeps;
+// the limit only stops NaN input from spinning forever
+const MAX_SWEEPS: usize = 64;
+
+// A = V diag(values) V^T for a symmetric A. Eigenvalues are sorted from largest to smallest,
+// column i of `vectors` is the unit eigenvector of `values[i]`.
+#[derive(Clone, Debug)]
+pub struct SymmetricEigen<T = f64> {
+  pub values: Vec<T>,
+  pub vectors: Mat<T>,
+}
+
+// A = U diag(singular_values) V^T. For an `m x n` matrix with k = min(m, n), U is `m x k`,
+// V is `n x k`, and the singular values are sorted from largest to smallest. U and V both have
+// orthonormal columns, also when A is rank-deficient.
+#[derive(Clone, Debug)]
+pub struct Svd<T = f64> {
+  pub u: Mat<T>,
+  pub singular_values: Vec<T>,
+  pub v: Mat<T>,
+}
+
+// Result of `Mat::pca`. Data rows are samples and columns are features.
+#[derive(Clone, Debug)]
+pub struct Pca<T = f64> {
+  // The centered data expressed in the k principal components, `samples x k`
+  pub projection: Mat<T>,
+  // Unit direction of every component, `features x k`
+  pub components: Mat<T>,
+  // Sample variance of the data along every component, largest first
+  pub explained_variance: Vec<T>,
+  // Share of the total variance every component explains
+  pub explained_variance_ratio: Vec<T>,
+  // Column means that were subtracted before projecting, `1 x features`
+  pub mean: Mat<T>,
+}
+
+// Sine and cosine of the rotation that zeroes an off-diagonal element, from the cotangent of
+// twice its angle. The smaller of the two possible angles keeps the rotation stable.
+fn rotation<T: Element>(cot_2theta: T) -> (T, T) {
+  let t = cot_2theta.signum() / (cot_2theta.abs() + (cot_2theta * cot_2theta + T::one()).sqrt());
+  let c = T::one() / (t * t + T::one()).sqrt();
+  return (c, c * t);
+}
+
+// Replaces columns p and q of `mat` with (c p - s q, s p + c q)
+fn rotate_columns<T: Element>(mat: &mut Mat<T>, p: usize, q: usize, c: T, s: T) {
+  for k in 0..mat.rows {
+    let (x, y) = (mat[(k, p)], mat[(k, q)]);
+    mat[(k, p)] = c * x - s * y;
+    mat[(k, q)] = s * x + c * y;
+  }
+}
+
+// Overwrites column `j` of `mat` with a unit vector orthogonal to the `basis` columns, which
+// must already be orthonormal. Every standard basis vector is orthogonalized against them and
+// the one with the longest remainder is kept, which is never shorter than 1 / sqrt(rows).
+fn complete_column<T: Element>(mat: &mut Mat<T>, basis: &[usize], j: usize) {
+  let mut best = vec![T::zero(); mat.rows];
+  let mut best_length = T::zero();
+  for i in 0..mat.rows {
+    let mut candidate = vec![T::zero(); mat.rows];
+    candidate[i] = T::one();
+    // Orthogonalizing twice removes what rounding leaves after the first pass
+    for _ in 0..2 {
+      for &b in basis {
+        let projection = candidate
+          .iter()
+          .enumerate()
+          .fold(T::zero(), |sum, (k, &value)| sum + value * mat[(k, b)]);
+        for (k, value) in candidate.iter_mut().enumerate() {
+          *value -= projection * mat[(k, b)];
+        }
+      }
+    }
+    let length = candidate
+      .iter()
+      .fold(T::zero(), |sum, &value| sum + value * value)
+      .sqrt();
+    if length > best_length {
+      best = candidate;
+      best_length = length;
+    }
+  }
+  for (k, &value) in best.iter().enumerate() {
+    mat[(k, j)] = value / best_length;
+  }
+}
+
+// Sorts `values` from largest to smallest and reorders the columns of every matrix in
+// `columns` the same way
+fn sort_descending<T: Element>(values: &mut Vec<T>, columns: &mut [&mut Mat<T>]) {
+  let mut order: Vec<usize> = (0..values.len()).collect();
+  order.sort_by(|&i, &j| {
+    values[j]
+      .partial_cmp(&values[i])
+      .unwrap_or(std::cmp::Ordering::Equal)
+  });
+  *values = order.iter().map(|&i| values[i]).collect();
+  for mat in columns.iter_mut() {
+    let mut sorted = Mat::zeros(mat.rows, mat.cols);
+    for (to, &from) in order.iter().enumerate() {
+      for k in 0..mat.rows {
+        sorted[(k, to)] = mat[(k, from)];
+      }
+    }
+    **mat = sorted;
+  }
+}
+
+pub fn symmetric_eigen<T: Element, A: AsView<T>>(mat: &A) -> SymmetricEigen<T> {
+  return or_panic(try_symmetric_eigen(mat));
+}
+
+// Cyclic Jacobi: every sweep rotates each off-diagonal pair to zero in turn, until what is left
+// off the diagonal is rounding noise
+pub fn try_symmetric_eigen<T: Element, A: AsView<T>>(
+  mat: &A,
+) -> Result<SymmetricEigen<T>, MatError> {
+  let mat = mat.view();
+  check_symmetric("Eigen decomposition", mat)?;
+  let n = mat.rows;
+  let mut a = mat.to_mat();
+  let mut vectors = Mat::identity(n);
+  let threshold = T::epsilon() * mat.norm(Norm::Frobenius);
+
+  for _ in 0..MAX_SWEEPS {
+    let mut off_diagonal = T::zero();
+    for p in 0..n {
+      for q in p + 1..n {
+        off_diagonal += a[(p, q)] * a[(p, q)];
+      }
+    }
+    if off_diagonal.sqrt() <= threshold {
+      break;
+    }
+    for p in 0..n {
+      for q in p + 1..n {
+        if a[(p, q)] == T::zero() {
+          continue;
+        }
+        let (c, s) = rotation((a[(q, q)] - a[(p, p)]) / (T::constant(2.0) * a[(p, q)]));
+        // A J, then J^T (A J), and the same rotation collected in V
+        rotate_columns(&mut a, p, q, c, s);
+        for k in 0..n {
+          let (x, y) = (a[(p, k)], a[(q, k)]);
+          a[(p, k)] = c * x - s * y;
+          a[(q, k)] = s * x + c * y;
+        }
+        a[(p, q)] = T::zero();
+        a[(q, p)] = T::zero();
+        rotate_columns(&mut vectors, p, q, c, s);
+      }
+    }
+  }
+
+  let mut values = (0..n).map(|i| a[(i, i)]).collect();
+  sort_descending(&mut values, &mut [&mut vectors]);
+  return Ok(SymmetricEigen { values, vectors });
+}
+
+// Thin SVD by one-sided Jacobi: columns of a copy of A are rotated until they are mutually
+// orthogonal, their lengths are then the singular values
+pub fn svd<T: Element, A: AsView<T>>(mat: &A) -> Svd<T> {
+  let mat = mat.view();
+  if mat.rows < mat.cols {
+    // A^T = V S U^T, and the rotations work best with at least as many rows as columns
+    let Svd {
+      u,
+      singular_values,
+      v,
+    } = svd(&mat.transpose());
+    return Svd {
+      u: v,
+      singular_values,
+      v: u,
+    };
+  }
+
+  let n = mat.cols;
+  let mut u = mat.to_mat();
+  let mut v = Mat::identity(n);
+  for _ in 0..MAX_SWEEPS {
+    let mut rotated = false;
+    for p in 0..n {
+      for q in p + 1..n {
+        let (mut alpha, mut beta, mut gamma) = (T::zero(), T::zero(), T::zero());
+        for k in 0..u.rows {
+          alpha += u[(k, p)] * u[(k, p)];
+          beta += u[(k, q)] * u[(k, q)];
+          gamma += u[(k, p)] * u[(k, q)];
+        }
+        if gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+          continue;
+        }
+        rotated = true;
+        let (c, s) = rotation((beta - alpha) / (T::constant(2.0) * gamma));
+        rotate_columns(&mut u, p, q, c, s);
+        rotate_columns(&mut v, p, q, c, s);
+      }
+    }
+    if !rotated {
+      break;
+    }
+  }
+
+  let mut singular_values: Vec<T> = (0..n).map(|j| u.col(j).norm(Norm::Frobenius)).collect();
+  for (j, &sigma) in singular_values.iter().enumerate() {
+    for k in 0..u.rows {
+      u[(k, j)] = if sigma > T::zero() {
+        u[(k, j)] / sigma
+      } else {
+        T::zero()
+      };
+    }
+  }
+  // A zero singular value leaves no direction in its column of U, so it is filled with one
+  // orthogonal to all the others to keep U^T U = I
+  let mut basis: Vec<usize> = (0..n).filter(|&j| singular_values[j] > T::zero()).collect();
+  for (j, &sigma) in singular_values.iter().enumerate() {
+    if sigma <= T::zero() {
+      complete_column(&mut u, &basis, j);
+      basis.push(j);
+    }
+  }
+  sort_descending(&mut singular_values, &mut [&mut u, &mut v]);
+  return Svd {
+    u,
+    singular_values,
+    v,
+  };
+}
+
+impl<T: Element> MatView<'_, T> {
+  // Principal component analysis keeping the `k` directions of largest variance
+  pub fn pca(&self, k: usize) -> Pca<T> {
+    return or_panic(self.try_pca(k));
+  }
+
+  pub fn try_pca(&self, k: usize) -> Result<Pca<T>, MatError> {
+    let available = self.rows.min(self.cols);
+    if k == 0 || k > available {
+      return Err(MatError::InvalidComponentCount {
+        requested: k,
+        available,
+      });
+    }
+    let mean = self.mean_axis(Axis::Column);
+    let centered = subtraction(self, &mean);
+    let decomposition = svd(&centered);
+
+    let components = decomposition.v.slice(.., ..k).to_mat();
+    let projection = dot_product(&centered, &components);
+    // Sample variance, so one sample less than there are rows
+    let degrees_of_freedom = T::constant(self.rows.saturating_sub(1).max(1) as f64);
+    let variances: Vec<T> = decomposition
+      .singular_values
+      .iter()
+      .map(|&sigma| sigma * sigma / degrees_of_freedom)
+      .collect();
+    let total = variances.iter().fold(T::zero(), |sum, &value| sum + value);
+    let explained_variance: Vec<T> = variances[..k].to_vec();
+    let explained_variance_ratio = explained_variance
+      .iter()
+      .map(|&value| {
+        if total > T::zero() {
+          value / total
+        } else {
+          T::zero()
+        }
+      })
+      .collect();
+
+    return Ok(Pca {
+      projection,
+      components,
+      explained_variance,
+      explained_variance_ratio,
+      mean,
+    });
+  }
+}
+
+impl<T: Element> Mat<T> {
+  pub fn pca(&self, k: usize) -> Pca<T> {
+    return self.view().pca(k);
+  }
+
+  pub fn try_pca(&self, k: usize) -> Result<Pca<T>, MatError> {
+    return self.view().try_pca(k);
+  }
+}
+
+impl<T: Element> Pca<T> {
+  // Projects new samples with the same features onto the components, e.g. a test set after
+  // fitting on the training set
+  pub fn transform<A: AsView<T>>(&self, data: &A) -> Mat<T> {
+    return or_panic(self.try_transform(data));
+  }
+
+  pub fn try_transform<A: AsView<T>>(&self, data: &A) -> Result<Mat<T>, MatError> {
+    let data = data.view();
+    if data.cols != self.mean.cols {
+      return Err(MatError::ShapeMismatch {
+        operation: "PCA transform",
+        left: (data.rows, data.cols),
+        right: (self.components.rows, self.components.cols),
+      });
+    }
+    return Ok(dot_product(
+      &subtraction(&data, &self.mean),
+      &self.components,
+    ));
+  }
+}
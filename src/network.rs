@@ -1,82 +1,461 @@
-#[path = "utils/macros.rs"]
-mod macros;
-
 pub mod network {
-  use nn::matrix::{addition, dot_product, Mat};
-  use std::ptr;
+  use crate::activation::activation::Activation;
+  use crate::loss::loss::Loss;
+  use crate::matrix::{
+    dot_product, dot_product_transposed, mat_copy, mat_row, AsView, Element, Mat, MatError, MatView,
+  };
+  use std::fmt;
+
+  // Why a dataset or matrix was rejected by a `try_*` method of `Network` or `Trainer`
+  #[derive(Clone, Debug, PartialEq)]
+  pub enum NnError {
+    Mat(MatError),
+    // Inputs and targets hold a different number of samples
+    SampleCountMismatch {
+      inputs: (usize, usize),
+      targets: (usize, usize),
+    },
+    InputWidthMismatch {
+      inputs: (usize, usize),
+      expected: usize,
+    },
+    OutputWidthMismatch {
+      targets: (usize, usize),
+      expected: usize,
+    },
+    // A `Trainer` whose `batch_size` was set to 0 after construction
+    ZeroBatchSize,
+  }
+
+  impl fmt::Display for NnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      return match self {
+        NnError::Mat(error) => write!(f, "{}", error),
+        NnError::SampleCountMismatch { inputs, targets } => write!(
+          f,
+          "Inputs and targets must have the same number of rows. Got inputs: ({}x{}) and targets: ({}x{})",
+          inputs.0, inputs.1, targets.0, targets.1
+        ),
+        NnError::InputWidthMismatch { inputs, expected } => write!(
+          f,
+          "Inputs must have one column per input. Got inputs: ({}x{}) for {} inputs",
+          inputs.0, inputs.1, expected
+        ),
+        NnError::OutputWidthMismatch { targets, expected } => write!(
+          f,
+          "Targets must have one column per output. Got targets: ({}x{}) for {} outputs",
+          targets.0, targets.1, expected
+        ),
+        NnError::ZeroBatchSize => write!(f, "Batch size must be greater than 0"),
+      };
+    }
+  }
+
+  impl std::error::Error for NnError {}
+
+  impl From<MatError> for NnError {
+    fn from(error: MatError) -> NnError {
+      return NnError::Mat(error);
+    }
+  }
+
+  // Unwraps the result of a `try_*` method, panicking with the error message
+  fn or_panic<R>(result: Result<R, NnError>) -> R {
+    return match result {
+      Ok(value) => value,
+      Err(error) => panic!("{}", error),
+    };
+  }
+
+  // Gradient of the cost with respect to every weight and bias of a `Network`,
+  // laid out exactly like the network's own `weights` and `bias`.
+  pub struct Gradients<T = f64> {
+    pub weights: Vec<Mat<T>>,
+    pub bias: Vec<Mat<T>>,
+  }
+
+  impl<T: Element> Gradients<T> {
+    pub fn new(network: &Network<T>) -> Gradients<T> {
+      let mut weights: Vec<Mat<T>> = Vec::with_capacity(network.count);
+      let mut bias: Vec<Mat<T>> = Vec::with_capacity(network.count);
+      for (w, b) in network.get_weights().iter().zip(network.get_bias().iter()) {
+        weights.push(Mat::zeros(w.rows(), w.cols()));
+        bias.push(Mat::zeros(b.rows(), b.cols()));
+      }
+      return Gradients { weights, bias };
+    }
+  }
+
+  // Read-only look at one layer: the parameters feeding it, its activation and its last output
+  pub struct Layer<'a, T = f64> {
+    pub weights: &'a Mat<T>,
+    pub bias: &'a Mat<T>,
+    pub activation: Activation,
+    pub output: &'a Mat<T>,
+  }
 
-  pub struct Network {
+  // Parameters are f64 unless another `Element` type is named, e.g. `Network<f32>`
+  pub struct Network<T = f64> {
     pub count: usize,
-    pub weights: *mut Mat,
-    pub bias: *mut Mat,
-    pub activations: *mut Mat,
+    weights: Vec<Mat<T>>,
+    bias: Vec<Mat<T>>,
+    // activations[0] is the input layer, activations[i + 1] the output of layer i
+    activations: Vec<Mat<T>>,
+    // activation_functions[i] is applied to the output of layer i
+    pub activation_functions: Vec<Activation>,
+    // Weighted inputs of every layer from the last `forward()`, before the activation
+    pre_activations: Vec<Mat<T>>,
   }
 
+  // Like `Mat::new`, these constructors build the default f64 network so they need no type
+  // annotation. `Network::<f32>::from_architecture` builds any other element type.
   impl Network {
+    // Every layer uses the sigmoid activation
     pub fn new(arch: &[usize]) -> Network {
+      assert!(
+        !arch.is_empty(),
+        "Architecture must have at least one layer"
+      );
+      return Network::with_activations(arch, &vec![Activation::Sigmoid; arch.len() - 1]);
+    }
+
+    // `arch` lists the layer sizes starting with the inputs, `activations` holds one activation
+    // per layer after the inputs, e.g. `&[2, 8, 1]` with `&[Activation::Relu, Activation::Identity]`
+    // for a regression network with a linear output.
+    pub fn with_activations(arch: &[usize], activations: &[Activation]) -> Network {
+      return Network::from_architecture(arch, activations);
+    }
+  }
+
+  impl<T: Element> Network<T> {
+    // Same as `with_activations`, for any element type
+    pub fn from_architecture(arch: &[usize], activations: &[Activation]) -> Network<T> {
       let arch_count = arch.len();
       assert!(arch_count > 0, "Architecture must have at least one layer");
+      assert!(
+        activations.len() == arch_count - 1,
+        "Expected one activation per layer. Got {} activations for {} layers",
+        activations.len(),
+        arch_count - 1
+      );
+
+      let count = arch_count - 1;
+      let mut weights: Vec<Mat<T>> = Vec::with_capacity(count);
+      let mut bias: Vec<Mat<T>> = Vec::with_capacity(count);
+      let mut layer_activations: Vec<Mat<T>> = Vec::with_capacity(count + 1);
+
+      layer_activations.push(Mat::zeros(1, arch[0]));
+      for i in 0..count {
+        weights.push(Mat::zeros(arch[i], arch[i + 1]));
+        bias.push(Mat::zeros(1, arch[i + 1]));
+        layer_activations.push(Mat::zeros(1, arch[i + 1]));
+      }
+
+      return Network {
+        count,
+        weights,
+        bias,
+        activations: layer_activations,
+        activation_functions: activations.to_vec(),
+        pre_activations: arch[1..].iter().map(|&cols| Mat::zeros(1, cols)).collect(),
+      };
+    }
+
+    pub fn get_weights(&self) -> &[Mat<T>] {
+      return &self.weights;
+    }
+
+    pub fn get_bias(&self) -> &[Mat<T>] {
+      return &self.bias;
+    }
 
-      let mut nn = Network {
-        count: arch_count - 1,
-        weights: ptr::null_mut(),
-        bias: ptr::null_mut(),
-        activations: ptr::null_mut(),
+    pub fn get_activations(&self) -> &[Mat<T>] {
+      return &self.activations;
+    }
+
+    pub fn layer(&self, i: usize) -> Layer<'_, T> {
+      return Layer {
+        weights: &self.weights[i],
+        bias: &self.bias[i],
+        activation: self.activation_functions[i],
+        output: &self.activations[i + 1],
       };
+    }
+
+    pub fn weights_mut(&mut self, i: usize) -> &mut Mat<T> {
+      return &mut self.weights[i];
+    }
+
+    pub fn bias_mut(&mut self, i: usize) -> &mut Mat<T> {
+      return &mut self.bias[i];
+    }
+
+    pub fn input(&self) -> &Mat<T> {
+      return &self.activations[0];
+    }
 
-      let mut weights: Vec<Mat> = Vec::with_capacity(nn.count);
-      let mut bias: Vec<Mat> = Vec::with_capacity(nn.count);
-      let mut activations: Vec<Mat> = Vec::with_capacity(nn.count + 1);
+    // The input layer buffer. `forward` overwrites it with the sample it is given.
+    pub fn input_mut(&mut self) -> &mut Mat<T> {
+      return &mut self.activations[0];
+    }
 
-      for i in 0..nn.count {
-        let weights_mat = Mat::new(arch[i], arch[i + 1]);
-        let bias_mat = Mat::new(1, arch[i + 1]);
-        let activations_mat = Mat::new(1, arch[i + 1]);
+    pub fn output(&self) -> &Mat<T> {
+      return &self.activations[self.count];
+    }
 
-        weights.push(weights_mat);
-        bias.push(bias_mat);
-        activations.push(activations_mat);
+    // Runs one `1 x inputs` sample through every layer and returns the output layer. The
+    // intermediate results stay in the layer buffers for `backprop` and `layer()`.
+    pub fn forward(&mut self, input: &impl AsView<T>) -> &Mat<T> {
+      return or_panic(self.try_forward(input));
+    }
+
+    // Like `forward`, but rejects an input of the wrong shape instead of panicking
+    pub fn try_forward(&mut self, input: &impl AsView<T>) -> Result<&Mat<T>, NnError> {
+      let input = input.view();
+      self.check_inputs(input)?;
+      if input.rows() != 1 {
+        return Err(NnError::Mat(MatError::ShapeMismatch {
+          operation: "Forward",
+          left: (input.rows(), input.cols()),
+          right: (1, self.input().cols()),
+        }));
+      }
+      self.forward_unchecked(input);
+      return Ok(self.output());
+    }
+
+    // The body of `forward` for an input that was already checked
+    fn forward_unchecked(&mut self, input: MatView<T>) {
+      mat_copy(&mut self.activations[0], &input);
+      for i in 0..self.count {
+        let z = &self.activations[i] * &self.weights[i] + &self.bias[i];
+        mat_copy(&mut self.pre_activations[i], &z);
+        self.activations[i + 1] = z;
+        self.activations[i + 1].apply(self.activation_functions[i]);
       }
+    }
 
-      nn.weights = weights.as_mut_ptr();
-      nn.bias = bias.as_mut_ptr();
-      nn.activations = activations.as_mut_ptr();
+    // Runs every row of `inputs` through the network at once and returns one row of outputs
+    // per input row. Each bias row is broadcast over the whole batch. Unlike `forward()` this
+    // leaves the layer buffers alone.
+    pub fn predict(&self, inputs: &impl AsView<T>) -> Mat<T> {
+      return or_panic(self.try_predict(inputs));
+    }
+
+    // Like `predict`, but rejects inputs of the wrong width instead of panicking
+    pub fn try_predict(&self, inputs: &impl AsView<T>) -> Result<Mat<T>, NnError> {
+      let inputs = inputs.view();
+      self.check_inputs(inputs)?;
+      return Ok(self.predict_unchecked(inputs));
+    }
 
-      // Prevent `Vec` from deallocating the memory when it goes out of scope
-      std::mem::forget(weights);
-      std::mem::forget(bias);
-      std::mem::forget(activations);
+    // The body of `predict` for inputs that were already checked
+    fn predict_unchecked(&self, inputs: MatView<T>) -> Mat<T> {
+      let mut outputs = inputs.to_mat();
+      for i in 0..self.count {
+        outputs = dot_product(&outputs, &self.weights[i]) + &self.bias[i];
+        outputs.apply(self.activation_functions[i]);
+      }
+      return outputs;
+    }
 
-      return nn;
+    // Mean `loss` over all rows of `inputs`/`outputs`, evaluated on one batched `predict`.
+    // Strided views into a shared training buffer work as well as plain matrices.
+    pub fn cost(&self, inputs: &impl AsView<T>, outputs: &impl AsView<T>, loss: &dyn Loss<T>) -> T {
+      return or_panic(self.try_cost(inputs, outputs, loss));
     }
 
-    pub fn get_weights(&self) -> Vec<Mat> {
-      unsafe {
-        return std::slice::from_raw_parts(self.weights, self.count).to_vec();
+    // Like `cost`, but rejects a dataset that does not fit the network instead of panicking
+    pub fn try_cost(
+      &self,
+      inputs: &impl AsView<T>,
+      outputs: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+    ) -> Result<T, NnError> {
+      let (inputs, outputs) = (inputs.view(), outputs.view());
+      self.try_check_dataset(&inputs, &outputs)?;
+      let predictions = self.predict_unchecked(inputs);
+      let mut cost = T::zero();
+      for sample in 0..inputs.rows() {
+        cost += loss.value(mat_row(&predictions, sample), mat_row(&outputs, sample));
       }
+      return Ok(cost / T::constant(inputs.rows() as f64));
     }
 
-    pub fn get_bias(&self) -> Vec<Mat> {
-      unsafe {
-        return std::slice::from_raw_parts(self.bias, self.count).to_vec();
+    // Averages the gradient of `loss` over every row of `inputs`/`targets`.
+    // Each row is run through `forward()` and the error is walked back through the layers.
+    pub fn backprop(
+      &mut self,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+    ) -> Gradients<T> {
+      return or_panic(self.try_backprop(inputs, targets, loss));
+    }
+
+    // Like `backprop`, but rejects a dataset that does not fit the network instead of panicking
+    pub fn try_backprop(
+      &mut self,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+    ) -> Result<Gradients<T>, NnError> {
+      let (inputs, targets) = (inputs.view(), targets.view());
+      self.try_check_dataset(&inputs, &targets)?;
+      return Ok(self.backprop_unchecked(inputs, targets, loss));
+    }
+
+    // The body of `backprop` for a dataset that was already checked
+    fn backprop_unchecked(
+      &mut self,
+      inputs: MatView<T>,
+      targets: MatView<T>,
+      loss: &dyn Loss<T>,
+    ) -> Gradients<T> {
+      let mut grads = Gradients::new(self);
+      for sample in 0..inputs.rows() {
+        self.forward_unchecked(mat_row(&inputs, sample));
+
+        // deltas holds dC/da of the layer currently being processed, starting at the output
+        let mut deltas = loss.derivative(self.output().view(), mat_row(&targets, sample));
+
+        for l in (0..self.count).rev() {
+          let (a_prev, a, w) = (
+            &self.activations[l],
+            &self.activations[l + 1],
+            &self.weights[l],
+          );
+          let dz = self.activation_functions[l].backward(&self.pre_activations[l], a, &deltas);
+          grads.bias[l] += &dz;
+          // The outer product a_prev^T * dz, with the row a_prev read as a column
+          grads.weights[l] += dot_product(&a_prev.reshape(a_prev.cols(), 1), &dz);
+          deltas = dot_product_transposed(&dz, w);
+        }
+      }
+
+      let n = T::constant(inputs.rows() as f64);
+      for l in 0..self.count {
+        grads.weights[l] /= n;
+        grads.bias[l] /= n;
       }
+      return grads;
     }
 
-    pub fn get_activations(&self) -> Vec<Mat> {
-      unsafe {
-        return std::slice::from_raw_parts(self.activations, self.count + 1).to_vec();
+    // Takes one gradient descent step: every parameter moves against its gradient.
+    pub fn apply_gradients(&mut self, grads: &Gradients<T>, learning_rate: T) {
+      for l in 0..self.count {
+        self.weights[l] -= &grads.weights[l] * learning_rate;
+        self.bias[l] -= &grads.bias[l] * learning_rate;
       }
     }
-    pub fn forward(&mut self) {
-      for i in 0..self.count {
-        unsafe {
-          let dot = dot_product(&(*self.activations.add(i)), &(*self.weights.add(i)));
-          *self.activations.add(i + 1) = dot;
-          *self.activations.add(i + 1) =
-            addition(&(*self.activations.add(i + 1)), &(*self.bias.add(i)));
-          (*self.activations.add(i + 1)).sigmoid();
+
+    // Estimates the gradient by nudging every weight and bias by `eps` in both directions and
+    // measuring the change in cost. Much slower than `backprop`, but shares none of its math.
+    pub fn finite_diff(
+      &mut self,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+      eps: T,
+    ) -> Gradients<T> {
+      let (inputs, targets) = (inputs.view(), targets.view());
+      let mut grads = Gradients::new(self);
+      for l in 0..self.count {
+        grads.weights[l] =
+          self.finite_diff_param(inputs, targets, loss, eps, |nn| &mut nn.weights[l]);
+        grads.bias[l] = self.finite_diff_param(inputs, targets, loss, eps, |nn| &mut nn.bias[l]);
+      }
+      return grads;
+    }
+
+    // Central difference of the cost for every entry of the parameter `select` points at
+    fn finite_diff_param(
+      &mut self,
+      inputs: MatView<T>,
+      targets: MatView<T>,
+      loss: &dyn Loss<T>,
+      eps: T,
+      select: impl Fn(&mut Network<T>) -> &mut Mat<T>,
+    ) -> Mat<T> {
+      let (rows, cols) = (select(self).rows(), select(self).cols());
+      let mut grad = Mat::zeros(rows, cols);
+      for i in 0..rows {
+        for j in 0..cols {
+          let saved = select(self)[(i, j)];
+          select(self)[(i, j)] = saved + eps;
+          let cost_plus = self.cost(&inputs, &targets, loss);
+          select(self)[(i, j)] = saved - eps;
+          let cost_minus = self.cost(&inputs, &targets, loss);
+          select(self)[(i, j)] = saved;
+          grad[(i, j)] = (cost_plus - cost_minus) / (T::constant(2.0) * eps);
+        }
+      }
+      return grad;
+    }
+
+    // Compares `backprop` against `finite_diff` and returns the largest relative error
+    // |a - n| / (|a| + |n|) of each layer's weights and bias. The denominator is floored at
+    // `eps` so entries where both gradients are practically zero do not blow up.
+    pub fn gradient_check(
+      &mut self,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+      eps: T,
+    ) -> Vec<T> {
+      let analytic = self.backprop(inputs, targets, loss);
+      let numeric = self.finite_diff(inputs, targets, loss, eps);
+      let mut errors = vec![T::zero(); self.count];
+      for (l, error) in errors.iter_mut().enumerate() {
+        let pairs = [
+          (&analytic.weights[l], &numeric.weights[l]),
+          (&analytic.bias[l], &numeric.bias[l]),
+        ];
+        for (a, n) in pairs {
+          for i in 0..a.rows() {
+            for j in 0..a.cols() {
+              let (a_value, n_value) = (a[(i, j)], n[(i, j)]);
+              let scale = (a_value.abs() + n_value.abs()).max(eps);
+              *error = error.max((a_value - n_value).abs() / scale);
+            }
+          }
         }
       }
+      return errors;
+    }
+
+    fn check_inputs(&self, inputs: MatView<T>) -> Result<(), NnError> {
+      if inputs.cols() != self.input().cols() {
+        return Err(NnError::InputWidthMismatch {
+          inputs: (inputs.rows(), inputs.cols()),
+          expected: self.input().cols(),
+        });
+      }
+      return Ok(());
+    }
+
+    // Checks that `inputs`/`targets` fit the network without running it, e.g. before a long
+    // training run changes anything
+    pub fn try_check_dataset(
+      &self,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+    ) -> Result<(), NnError> {
+      let (inputs, targets) = (inputs.view(), targets.view());
+      if inputs.rows() != targets.rows() {
+        return Err(NnError::SampleCountMismatch {
+          inputs: (inputs.rows(), inputs.cols()),
+          targets: (targets.rows(), targets.cols()),
+        });
+      }
+      self.check_inputs(inputs)?;
+      if targets.cols() != self.output().cols() {
+        return Err(NnError::OutputWidthMismatch {
+          targets: (targets.rows(), targets.cols()),
+          expected: self.output().cols(),
+        });
+      }
+      return Ok(());
     }
 
     pub fn print(&self, overwrite_padding: Option<usize>, overwrite_precision: Option<usize>) {
@@ -86,49 +465,24 @@ pub mod network {
       println!("Network: ");
       println!("[",);
       for i in 0..self.count {
-        unsafe {
-          println!("{:padding$}weights[{}]: ", "", i, padding = padding / 2);
-          println!("{:padding$}[", "", padding = padding / 2);
-          (*self.weights.add(i)).print("weights", Some(padding), Some(precision));
-          println!("{:padding$}]", "", padding = padding / 2);
-        }
-        unsafe {
-          println!("{:padding$}bias[{}]: ", "", i, padding = padding / 2);
-          println!("{:padding$}[", "", padding = padding / 2);
-          (*self.bias.add(i)).print("bias", Some(padding), Some(precision));
-          println!("{:padding$}]", "", padding = padding / 2);
-        }
+        println!("{:padding$}weights[{}]: ", "", i, padding = padding / 2);
+        println!("{:padding$}[", "", padding = padding / 2);
+        self.weights[i].print("weights", Some(padding), Some(precision));
+        println!("{:padding$}]", "", padding = padding / 2);
+
+        println!("{:padding$}bias[{}]: ", "", i, padding = padding / 2);
+        println!("{:padding$}[", "", padding = padding / 2);
+        self.bias[i].print("bias", Some(padding), Some(precision));
+        println!("{:padding$}]", "", padding = padding / 2);
       }
       println!("]");
     }
-    
-    pub fn rand(&mut self, low: f64, high: f64) {
-      for i in 0..self.count {
-        unsafe {
-          (*self.weights.add(i)).rand(low, high);
-          (*self.bias.add(i)).rand(low, high);
-        }
-      }
-    }
-
-    fn drop(&mut self) {
-      let mut weights = unsafe { Vec::from_raw_parts(self.weights, self.count, self.count) };
-      let mut bias = unsafe { Vec::from_raw_parts(self.bias, self.count, self.count) };
-      let mut activations =
-        unsafe { Vec::from_raw_parts(self.activations, self.count + 1, self.count + 1) };
 
-      // Explicitly drop each matrix in reverse order
-      for i in (0..self.count).rev() {
-        let _ = std::mem::replace(&mut weights[i], Mat::new(0, 0));
-        let _ = std::mem::replace(&mut bias[i], Mat::new(0, 0));
-        let _ = std::mem::replace(&mut activations[i], Mat::new(0, 0));
+    pub fn rand(&mut self, low: T, high: T) {
+      for i in 0..self.count {
+        self.weights[i].rand(low, high);
+        self.bias[i].rand(low, high);
       }
-      let _ = std::mem::replace(&mut activations[self.count], Mat::new(0, 0));
-
-      // Deallocate the memory
-      std::mem::drop(weights);
-      std::mem::drop(bias);
-      std::mem::drop(activations);
     }
   }
 }
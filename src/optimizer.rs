@@ -0,0 +1,385 @@
+pub mod optimizer {
+  use crate::matrix::{Element, Mat};
+  use crate::network::network::{Gradients, Network};
+
+  // Turns gradients into parameter updates. Optimizers with memory keep one state `Mat` per
+  // parameter, looked up by the parameter's slot, so a single optimizer must only ever train
+  // a single network.
+  pub trait Optimizer<T: Element = f64> {
+    // Moves `param` against `grad`. `slot` names the parameter and must stay the same for it
+    // from one step to the next.
+    fn update(&mut self, slot: usize, param: &mut Mat<T>, grad: &Mat<T>);
+
+    // Runs once at the start of every `step`, before any `update`
+    fn begin_step(&mut self) {}
+
+    // Updates every parameter of `network`. The weights of layer l are slot 2l, its bias 2l + 1.
+    fn step(&mut self, network: &mut Network<T>, grads: &Gradients<T>) {
+      self.begin_step();
+      for l in 0..network.count {
+        self.update(2 * l, network.weights_mut(l), &grads.weights[l]);
+        self.update(2 * l + 1, network.bias_mut(l), &grads.bias[l]);
+      }
+    }
+  }
+
+  // Plain gradient descent, the same step as `Network::apply_gradients`
+  #[derive(Clone, Debug)]
+  pub struct Sgd<T = f64> {
+    pub learning_rate: T,
+  }
+
+  // Gradient descent with a velocity that keeps moving in the direction of past steps
+  #[derive(Clone, Debug)]
+  pub struct Momentum<T = f64> {
+    pub learning_rate: T,
+    // Share of the previous velocity that carries over, usually 0.9
+    pub momentum: T,
+    velocity: Vec<Option<Mat<T>>>,
+  }
+
+  // Momentum that steps with the gradient at the point the velocity is about to carry the
+  // parameters to, which damps overshooting
+  #[derive(Clone, Debug)]
+  pub struct Nesterov<T = f64> {
+    pub learning_rate: T,
+    pub momentum: T,
+    velocity: Vec<Option<Mat<T>>>,
+  }
+
+  // Divides every step by the root of all squared gradients seen so far, so rarely updated
+  // parameters keep larger steps. The steps only ever shrink.
+  #[derive(Clone, Debug)]
+  pub struct Adagrad<T = f64> {
+    pub learning_rate: T,
+    pub epsilon: T,
+    sum_of_squares: Vec<Option<Mat<T>>>,
+  }
+
+  // Like `Adagrad` with a moving average of the squared gradients instead of their sum
+  #[derive(Clone, Debug)]
+  pub struct RmsProp<T = f64> {
+    pub learning_rate: T,
+    // Weight of the old average against the newest squared gradient
+    pub decay: T,
+    pub epsilon: T,
+    mean_square: Vec<Option<Mat<T>>>,
+  }
+
+  // Moving averages of the gradient and of its square, corrected for starting at zero
+  #[derive(Clone, Debug)]
+  pub struct Adam<T = f64> {
+    pub learning_rate: T,
+    pub beta1: T,
+    pub beta2: T,
+    pub epsilon: T,
+    moments: Moments<T>,
+  }
+
+  // Adam with decoupled weight decay: every step also shrinks the parameters by
+  // `learning_rate * weight_decay`, independent of the gradient
+  #[derive(Clone, Debug)]
+  pub struct AdamW<T = f64> {
+    pub learning_rate: T,
+    pub beta1: T,
+    pub beta2: T,
+    pub epsilon: T,
+    pub weight_decay: T,
+    moments: Moments<T>,
+  }
+
+  // The state of parameter `slot`, all zeros the first time the slot is seen
+  fn state<'a, T: Element>(
+    states: &'a mut Vec<Option<Mat<T>>>,
+    slot: usize,
+    param: &Mat<T>,
+  ) -> &'a mut Mat<T> {
+    if states.len() <= slot {
+      states.resize_with(slot + 1, || None);
+    }
+    let state = states[slot].get_or_insert_with(|| Mat::zeros(param.rows(), param.cols()));
+    assert!(
+      state.rows() == param.rows() && state.cols() == param.cols(),
+      "Optimizer state of slot {} is ({}x{}), but the parameter is ({}x{})",
+      slot,
+      state.rows(),
+      state.cols(),
+      param.rows(),
+      param.cols()
+    );
+    return state;
+  }
+
+  fn check_gradient<T>(param: &Mat<T>, grad: &Mat<T>) {
+    assert!(
+      param.rows() == grad.rows() && param.cols() == grad.cols(),
+      "Gradient dimensions must match the parameter. Got parameter: ({}x{}) and gradient: ({}x{})",
+      param.rows(),
+      param.cols(),
+      grad.rows(),
+      grad.cols()
+    );
+  }
+
+  fn check_momentum<T: Element>(momentum: T) {
+    assert!(
+      momentum >= T::zero() && momentum < T::one(),
+      "Momentum must be in [0, 1). Got {}",
+      momentum
+    );
+  }
+
+  // v = momentum * v + g, then the parameter moves by v, or for Nesterov by the gradient plus
+  // the velocity of the next step
+  fn momentum_update<T: Element>(
+    velocity: &mut Mat<T>,
+    param: &mut Mat<T>,
+    grad: &Mat<T>,
+    learning_rate: T,
+    momentum: T,
+    nesterov: bool,
+  ) {
+    for i in 0..param.rows() {
+      for j in 0..param.cols() {
+        let v = momentum * velocity[(i, j)] + grad[(i, j)];
+        velocity[(i, j)] = v;
+        let direction = if nesterov {
+          grad[(i, j)] + momentum * v
+        } else {
+          v
+        };
+        param[(i, j)] -= learning_rate * direction;
+      }
+    }
+  }
+
+  // g / (sqrt(s) + epsilon) for every entry, after `accumulate` folded g into the state s
+  fn scaled_update<T: Element>(
+    squares: &mut Mat<T>,
+    param: &mut Mat<T>,
+    grad: &Mat<T>,
+    learning_rate: T,
+    epsilon: T,
+    accumulate: impl Fn(T, T) -> T,
+  ) {
+    for i in 0..param.rows() {
+      for j in 0..param.cols() {
+        let g = grad[(i, j)];
+        let s = accumulate(squares[(i, j)], g * g);
+        squares[(i, j)] = s;
+        param[(i, j)] -= learning_rate * g / (s.sqrt() + epsilon);
+      }
+    }
+  }
+
+  // First and second moments of every parameter and the number of steps taken, shared by
+  // `Adam` and `AdamW`
+  #[derive(Clone, Debug)]
+  struct Moments<T> {
+    time_step: usize,
+    first: Vec<Option<Mat<T>>>,
+    second: Vec<Option<Mat<T>>>,
+  }
+
+  impl<T: Element> Moments<T> {
+    fn new() -> Moments<T> {
+      return Moments {
+        time_step: 0,
+        first: Vec::new(),
+        second: Vec::new(),
+      };
+    }
+
+    fn update(&mut self, slot: usize, param: &mut Mat<T>, grad: &Mat<T>, adam: (T, T, T, T)) {
+      let (learning_rate, beta1, beta2, epsilon) = adam;
+      // An `update` outside of `step` counts as the first step rather than dividing by zero
+      let t = self.time_step.max(1) as i32;
+      let correction1 = T::one() - beta1.powi(t);
+      let correction2 = T::one() - beta2.powi(t);
+      let first = state(&mut self.first, slot, param);
+      let second = state(&mut self.second, slot, param);
+      for i in 0..param.rows() {
+        for j in 0..param.cols() {
+          let g = grad[(i, j)];
+          let m = beta1 * first[(i, j)] + (T::one() - beta1) * g;
+          let v = beta2 * second[(i, j)] + (T::one() - beta2) * g * g;
+          first[(i, j)] = m;
+          second[(i, j)] = v;
+          param[(i, j)] -= learning_rate * (m / correction1) / ((v / correction2).sqrt() + epsilon);
+        }
+      }
+    }
+  }
+
+  impl<T: Element> Sgd<T> {
+    pub fn new(learning_rate: T) -> Sgd<T> {
+      return Sgd { learning_rate };
+    }
+  }
+
+  impl<T: Element> Momentum<T> {
+    pub fn new(learning_rate: T, momentum: T) -> Momentum<T> {
+      check_momentum(momentum);
+      return Momentum {
+        learning_rate,
+        momentum,
+        velocity: Vec::new(),
+      };
+    }
+  }
+
+  impl<T: Element> Nesterov<T> {
+    pub fn new(learning_rate: T, momentum: T) -> Nesterov<T> {
+      check_momentum(momentum);
+      return Nesterov {
+        learning_rate,
+        momentum,
+        velocity: Vec::new(),
+      };
+    }
+  }
+
+  impl<T: Element> Adagrad<T> {
+    // `epsilon` starts at 1e-8
+    pub fn new(learning_rate: T) -> Adagrad<T> {
+      return Adagrad {
+        learning_rate,
+        epsilon: T::constant(1e-8),
+        sum_of_squares: Vec::new(),
+      };
+    }
+  }
+
+  impl<T: Element> RmsProp<T> {
+    // `decay` starts at 0.9 and `epsilon` at 1e-8
+    pub fn new(learning_rate: T) -> RmsProp<T> {
+      return RmsProp {
+        learning_rate,
+        decay: T::constant(0.9),
+        epsilon: T::constant(1e-8),
+        mean_square: Vec::new(),
+      };
+    }
+  }
+
+  impl<T: Element> Adam<T> {
+    // The usual defaults: `beta1` 0.9, `beta2` 0.999 and `epsilon` 1e-8
+    pub fn new(learning_rate: T) -> Adam<T> {
+      return Adam {
+        learning_rate,
+        beta1: T::constant(0.9),
+        beta2: T::constant(0.999),
+        epsilon: T::constant(1e-8),
+        moments: Moments::new(),
+      };
+    }
+  }
+
+  impl<T: Element> AdamW<T> {
+    // Same defaults as `Adam::new`
+    pub fn new(learning_rate: T, weight_decay: T) -> AdamW<T> {
+      return AdamW {
+        learning_rate,
+        beta1: T::constant(0.9),
+        beta2: T::constant(0.999),
+        epsilon: T::constant(1e-8),
+        weight_decay,
+        moments: Moments::new(),
+      };
+    }
+  }
+
+  impl<T: Element> Optimizer<T> for Sgd<T> {
+    fn update(&mut self, _slot: usize, param: &mut Mat<T>, grad: &Mat<T>) {
+      check_gradient(param, grad);
+      *param -= grad * self.learning_rate;
+    }
+  }
+
+  impl<T: Element> Optimizer<T> for Momentum<T> {
+    fn update(&mut self, slot: usize, param: &mut Mat<T>, grad: &Mat<T>) {
+      check_gradient(param, grad);
+      let velocity = state(&mut self.velocity, slot, param);
+      momentum_update(
+        velocity,
+        param,
+        grad,
+        self.learning_rate,
+        self.momentum,
+        false,
+      );
+    }
+  }
+
+  impl<T: Element> Optimizer<T> for Nesterov<T> {
+    fn update(&mut self, slot: usize, param: &mut Mat<T>, grad: &Mat<T>) {
+      check_gradient(param, grad);
+      let velocity = state(&mut self.velocity, slot, param);
+      momentum_update(
+        velocity,
+        param,
+        grad,
+        self.learning_rate,
+        self.momentum,
+        true,
+      );
+    }
+  }
+
+  impl<T: Element> Optimizer<T> for Adagrad<T> {
+    fn update(&mut self, slot: usize, param: &mut Mat<T>, grad: &Mat<T>) {
+      check_gradient(param, grad);
+      let squares = state(&mut self.sum_of_squares, slot, param);
+      scaled_update(
+        squares,
+        param,
+        grad,
+        self.learning_rate,
+        self.epsilon,
+        |sum, square| sum + square,
+      );
+    }
+  }
+
+  impl<T: Element> Optimizer<T> for RmsProp<T> {
+    fn update(&mut self, slot: usize, param: &mut Mat<T>, grad: &Mat<T>) {
+      check_gradient(param, grad);
+      let decay = self.decay;
+      let squares = state(&mut self.mean_square, slot, param);
+      scaled_update(
+        squares,
+        param,
+        grad,
+        self.learning_rate,
+        self.epsilon,
+        |mean, square| decay * mean + (T::one() - decay) * square,
+      );
+    }
+  }
+
+  impl<T: Element> Optimizer<T> for Adam<T> {
+    fn begin_step(&mut self) {
+      self.moments.time_step += 1;
+    }
+
+    fn update(&mut self, slot: usize, param: &mut Mat<T>, grad: &Mat<T>) {
+      check_gradient(param, grad);
+      let hyperparameters = (self.learning_rate, self.beta1, self.beta2, self.epsilon);
+      self.moments.update(slot, param, grad, hyperparameters);
+    }
+  }
+
+  impl<T: Element> Optimizer<T> for AdamW<T> {
+    fn begin_step(&mut self) {
+      self.moments.time_step += 1;
+    }
+
+    fn update(&mut self, slot: usize, param: &mut Mat<T>, grad: &Mat<T>) {
+      check_gradient(param, grad);
+      // The decay uses the parameters from before this step's gradient update
+      *param *= T::one() - self.learning_rate * self.weight_decay;
+      let hyperparameters = (self.learning_rate, self.beta1, self.beta2, self.epsilon);
+      self.moments.update(slot, param, grad, hyperparameters);
+    }
+  }
+}
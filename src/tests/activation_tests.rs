@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+  use nn::activation::activation::Activation;
+  use nn::matrix::{Mat, MatError};
+
+  const ELEMENTWISE: [Activation; 8] = [
+    Activation::Sigmoid,
+    Activation::Tanh,
+    Activation::Relu,
+    Activation::LeakyRelu(0.01),
+    Activation::Elu(1.0),
+    Activation::Gelu,
+    Activation::Softplus,
+    Activation::Identity,
+  ];
+
+  fn row(values: &[f64]) -> Mat {
+    let mut mat = Mat::new(1, values.len());
+    for (j, value) in values.iter().enumerate() {
+      mat.set(0, j, *value);
+    }
+    return mat;
+  }
+
+  #[test]
+  fn test_forward_values() {
+    assert_eq!(Activation::Sigmoid.forward(0.0), Ok(0.5));
+    assert_eq!(Activation::Relu.forward(-2.0), Ok(0.0));
+    assert_eq!(Activation::Relu.forward(2.0), Ok(2.0));
+    assert_eq!(Activation::LeakyRelu(0.1).forward(-2.0), Ok(-0.2));
+    assert_eq!(Activation::Identity.forward(-7.5), Ok(-7.5));
+    assert!((Activation::Elu(1.0).forward(-1.0).unwrap() - (-1f64).exp_m1()).abs() < 1e-15);
+    assert!((Activation::Softplus.forward(0.0).unwrap() - 2f64.ln()).abs() < 1e-15);
+    assert!((Activation::Gelu.forward(1.0_f64).unwrap() - 0.8411919906082768).abs() < 1e-12);
+    // Large inputs must not overflow
+    assert_eq!(Activation::Softplus.forward(1000.0), Ok(1000.0));
+  }
+
+  #[test]
+  fn test_derivatives_match_finite_difference() {
+    let eps = 1e-6;
+    for activation in ELEMENTWISE {
+      for x in [-2.5_f64, -0.7, 0.3, 1.9] {
+        let numeric = (activation.forward(x + eps).unwrap() - activation.forward(x - eps).unwrap())
+          / (2.0 * eps);
+        let analytic = activation.derivative(x).unwrap();
+        assert!(
+          (analytic - numeric).abs() < 1e-6,
+          "{:?} at {}: derivative {} but finite difference gives {}",
+          activation,
+          x,
+          analytic,
+          numeric
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_apply_elementwise() {
+    let mut mat = row(&[-1.0, 0.0, 2.0]);
+    mat.apply(Activation::Relu);
+    assert_eq!(mat.get(0, 0), Some(0.0));
+    assert_eq!(mat.get(0, 1), Some(0.0));
+    assert_eq!(mat.get(0, 2), Some(2.0));
+  }
+
+  #[test]
+  fn test_apply_softmax_rows_sum_to_one() {
+    let mut mat = Mat::new(2, 3);
+    mat.set(0, 0, 1.0);
+    mat.set(0, 1, 2.0);
+    mat.set(0, 2, 3.0);
+    // Large values would overflow without the max shift
+    mat.set(1, 0, 1000.0);
+    mat.set(1, 1, 1000.0);
+    mat.set(1, 2, 1000.0);
+    mat.apply(Activation::Softmax);
+
+    for i in 0..2 {
+      let sum: f64 = (0..3).map(|j| mat.get(i, j).unwrap()).sum();
+      assert!((sum - 1.0).abs() < 1e-12);
+    }
+    assert!(mat.get(0, 2).unwrap() > mat.get(0, 1).unwrap());
+    assert!((mat.get(1, 0).unwrap() - 1.0 / 3.0).abs() < 1e-12);
+  }
+
+  #[test]
+  fn test_softmax_backward_matches_finite_difference() {
+    let eps = 1e-6;
+    let z = [0.5, -1.0, 2.0];
+    // Upstream gradient of the scalar L = sum(c_j * a_j)
+    let c = [1.0, -2.0, 0.5];
+    let softmax = |values: &[f64]| {
+      let mut mat = row(values);
+      mat.apply(Activation::Softmax);
+      return mat;
+    };
+    let scalar = |values: &[f64]| {
+      let a = softmax(values);
+      return (0..3).map(|j| c[j] * a.get(0, j).unwrap()).sum::<f64>();
+    };
+
+    let dz = Activation::Softmax.backward(&row(&z), &softmax(&z), &row(&c));
+
+    for j in 0..3 {
+      let mut plus = z.to_vec();
+      plus[j] += eps;
+      let mut minus = z.to_vec();
+      minus[j] -= eps;
+      let numeric = (scalar(&plus) - scalar(&minus)) / (2.0 * eps);
+      assert!((dz.get(0, j).unwrap() - numeric).abs() < 1e-6);
+    }
+  }
+
+  #[test]
+  fn test_softmax_has_no_scalar_value() {
+    assert_eq!(
+      Activation::Softmax.forward(1.0),
+      Err(MatError::NotElementwise {
+        operation: "Activation"
+      })
+    );
+    let error = Activation::Softmax.derivative(1.0).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "Activation derivative failed! Softmax works on whole rows, use Mat::apply or Activation::backward."
+    );
+  }
+}
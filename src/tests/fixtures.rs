@@ -0,0 +1,48 @@
+// Datasets and networks shared by the network, trainer and optimizer tests. Every test file
+// pulls this in with `#[path = "fixtures.rs"] mod fixtures;`.
+pub mod fixtures {
+  use nn::activation::activation::Activation;
+  use nn::matrix::{Element, Mat};
+  use nn::network::network::Network;
+
+  // XOR truth table as (inputs, outputs)
+  pub fn xor_data() -> (Mat, Mat) {
+    let mut inputs = Mat::new(4, 2);
+    let mut outputs = Mat::new(4, 1);
+    for i in 0..4 {
+      let (a, b) = (i / 2, i % 2);
+      inputs.set(i, 0, a as f64);
+      inputs.set(i, 1, b as f64);
+      outputs.set(i, 0, (a ^ b) as f64);
+    }
+    return (inputs, outputs);
+  }
+
+  // Fills every weight and bias with a fixed spread of values in [-1, 1], so training tests do
+  // not depend on a lucky random initialization
+  pub fn init_deterministic<T: Element>(network: &mut Network<T>) {
+    let mut k = 0;
+    for l in 0..network.count {
+      for is_bias in [false, true] {
+        let mat = if is_bias {
+          network.bias_mut(l)
+        } else {
+          network.weights_mut(l)
+        };
+        for i in 0..mat.rows() {
+          for j in 0..mat.cols() {
+            k += 1;
+            mat.set(i, j, T::constant((k as f64 * 0.618034).fract() * 2.0 - 1.0));
+          }
+        }
+      }
+    }
+  }
+
+  // A 2-4-1 tanh network that can learn `xor_data`, starting from `init_deterministic`
+  pub fn xor_network<T: Element>() -> Network<T> {
+    let mut network = Network::from_architecture(&[2, 4, 1], &[Activation::Tanh; 2]);
+    init_deterministic(&mut network);
+    return network;
+  }
+}
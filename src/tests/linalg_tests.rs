@@ -0,0 +1,441 @@
+#[cfg(test)]
+mod tests {
+  use nn::matrix::{
+    cholesky, det, dot_product, dot_product_transposed, inverse, lstsq, lu, qr, solve, svd,
+    symmetric_eigen, try_cholesky, try_det, try_inverse, try_lstsq, try_lu, try_solve,
+    try_symmetric_eigen, Mat, MatError, Norm,
+  };
+
+  fn assert_mat_close(actual: &Mat, expected: &Mat, tolerance: f64) {
+    assert_eq!(
+      (actual.rows(), actual.cols()),
+      (expected.rows(), expected.cols())
+    );
+    for i in 0..actual.rows() {
+      for j in 0..actual.cols() {
+        assert!(
+          (actual[(i, j)] - expected[(i, j)]).abs() < tolerance,
+          "({}, {}): expected {}, got {}",
+          i,
+          j,
+          expected[(i, j)],
+          actual[(i, j)]
+        );
+      }
+    }
+  }
+
+  // Diagonally dominant, so it is well conditioned and never singular
+  fn random_invertible(n: usize) -> Mat {
+    let mut mat = Mat::new(n, n);
+    mat.rand(-1.0, 1.0);
+    for i in 0..n {
+      mat[(i, i)] += n as f64;
+    }
+    return mat;
+  }
+
+  #[test]
+  fn test_lu_reconstructs_matrix() {
+    // The zero in the corner forces a row swap
+    let a = Mat::from_vec(3, 3, vec![0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 4.0, -2.0, 3.0]);
+    let decomposition = lu(&a);
+    assert_eq!(decomposition.permutation()[0], 2);
+    assert_mat_close(
+      &dot_product(&decomposition.l(), &decomposition.u()),
+      &dot_product(&decomposition.p(), &a),
+      1e-12,
+    );
+    for i in 0..3 {
+      assert_eq!(decomposition.l()[(i, i)], 1.0);
+      for j in 0..i {
+        assert_eq!(decomposition.u()[(i, j)], 0.0);
+        assert!(decomposition.l()[(i, j)].abs() <= 1.0);
+      }
+    }
+  }
+
+  #[test]
+  fn test_det() {
+    let a: Mat = Mat::from_vec(2, 2, vec![3.0, 8.0, 4.0, 6.0]);
+    assert!((det(&a) + 14.0).abs() < 1e-12);
+    let b: Mat = Mat::from_vec(3, 3, vec![6.0, 1.0, 1.0, 4.0, -2.0, 5.0, 2.0, 8.0, 7.0]);
+    assert!((det(&b) + 306.0).abs() < 1e-10);
+    // Swapping two rows flips the sign
+    let swapped: Mat = Mat::from_vec(2, 2, vec![4.0, 6.0, 3.0, 8.0]);
+    assert!((det(&swapped) - 14.0).abs() < 1e-12);
+    assert_eq!(det(&Mat::<f64>::identity(5)), 1.0);
+  }
+
+  #[test]
+  fn test_inverse() {
+    for n in [1, 2, 5, 20] {
+      let a = random_invertible(n);
+      let a_inv = inverse(&a);
+      assert_mat_close(&dot_product(&a, &a_inv), &Mat::identity(n), 1e-10);
+      assert_mat_close(&dot_product(&a_inv, &a), &Mat::identity(n), 1e-10);
+    }
+    let a = Mat::from_vec(2, 2, vec![4.0, 7.0, 2.0, 6.0]);
+    let expected = Mat::from_vec(2, 2, vec![0.6, -0.7, -0.2, 0.4]);
+    assert_mat_close(&inverse(&a), &expected, 1e-12);
+  }
+
+  #[test]
+  fn test_solve() {
+    let a = Mat::from_vec(3, 3, vec![2.0, 1.0, -1.0, -3.0, -1.0, 2.0, -2.0, 1.0, 2.0]);
+    let b = Mat::from_vec(3, 1, vec![8.0, -11.0, -3.0]);
+    assert_mat_close(
+      &solve(&a, &b),
+      &Mat::from_vec(3, 1, vec![2.0, 3.0, -1.0]),
+      1e-12,
+    );
+
+    // Several right hand sides at once, and a strided operand
+    let a = random_invertible(8);
+    let mut wide = Mat::new(8, 5);
+    wide.rand(-5.0, 5.0);
+    let b = wide.slice(.., 1..4);
+    let x = solve(&a, &b);
+    assert_mat_close(&dot_product(&a, &x), &b.to_mat(), 1e-10);
+    assert_mat_close(&x, &dot_product(&inverse(&a), &b), 1e-10);
+  }
+
+  #[test]
+  fn test_singular_matrices() {
+    let singular = Mat::from_vec(3, 3, (0..9).map(|x| x as f64).collect());
+    assert!(lu(&singular).is_singular());
+    assert_eq!(det(&singular), 0.0);
+    assert_eq!(
+      try_inverse(&singular).unwrap_err(),
+      MatError::Singular {
+        operation: "Inverse"
+      }
+    );
+    assert_eq!(
+      try_solve(&singular, &Mat::new(3, 1)).unwrap_err(),
+      MatError::Singular { operation: "Solve" }
+    );
+    assert!(lu(&Mat::new(2, 2)).is_singular());
+  }
+
+  #[test]
+  #[should_panic(expected = "Inverse failed! Matrix is singular.")]
+  fn test_inverse_of_singular_panics() {
+    inverse(&Mat::from_vec(2, 2, vec![1.0, 2.0, 2.0, 4.0]));
+  }
+
+  #[test]
+  fn test_shape_errors() {
+    assert_eq!(
+      try_det(&Mat::new(2, 3)).unwrap_err(),
+      MatError::NotSquare {
+        operation: "Determinant",
+        rows: 2,
+        cols: 3
+      }
+    );
+    assert!(matches!(
+      try_lu(&Mat::new(3, 2)),
+      Err(MatError::NotSquare { .. })
+    ));
+    assert_eq!(
+      try_solve(&Mat::<f64>::identity(3), &Mat::new(2, 1)).unwrap_err(),
+      MatError::ShapeMismatch {
+        operation: "Solve",
+        left: (3, 3),
+        right: (2, 1)
+      }
+    );
+  }
+
+  #[test]
+  fn test_f32_solve() {
+    let a = Mat::from_vec(2, 2, vec![2.0f32, 1.0, 1.0, 3.0]);
+    let b = Mat::from_vec(2, 1, vec![3.0f32, 5.0]);
+    let x = solve(&a, &b);
+    assert!((x[(0, 0)] - 0.8).abs() < 1e-6);
+    assert!((x[(1, 0)] - 1.4).abs() < 1e-6);
+    assert!((det(&a) - 5.0).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_qr_reconstructs_matrix() {
+    for (m, n) in [(4, 4), (7, 3), (3, 5), (1, 1)] {
+      let mut a = Mat::new(m, n);
+      a.rand(-2.0, 2.0);
+      let decomposition = qr(&a);
+      let (q, r) = (decomposition.q(), decomposition.r());
+      let k = m.min(n);
+      assert_eq!((q.rows(), q.cols(), r.rows(), r.cols()), (m, k, k, n));
+      assert_mat_close(&dot_product(&q, &r), &a, 1e-12);
+      // Q^T Q = I and R is upper triangular
+      assert_mat_close(&dot_product(&q.transpose(), &q), &Mat::identity(k), 1e-12);
+      for i in 0..k {
+        for j in 0..i.min(n) {
+          assert_eq!(r[(i, j)], 0.0);
+        }
+      }
+    }
+
+    // A zero column needs no reflection
+    let a = Mat::from_vec(3, 2, vec![0.0, 1.0, 0.0, 2.0, 0.0, 3.0]);
+    let decomposition = qr(&a);
+    assert_mat_close(
+      &dot_product(&decomposition.q(), &decomposition.r()),
+      &a,
+      1e-12,
+    );
+  }
+
+  #[test]
+  fn test_cholesky() {
+    let a = Mat::from_vec(
+      3,
+      3,
+      vec![4.0, 12.0, -16.0, 12.0, 37.0, -43.0, -16.0, -43.0, 98.0],
+    );
+    let l = cholesky(&a);
+    let expected = Mat::from_vec(3, 3, vec![2.0, 0.0, 0.0, 6.0, 1.0, 0.0, -8.0, 5.0, 3.0]);
+    assert_mat_close(&l, &expected, 1e-12);
+
+    // Any B B^T plus a positive diagonal is positive definite
+    let mut b = Mat::new(6, 6);
+    b.rand(-1.0, 1.0);
+    let mut spd = dot_product_transposed(&b, &b);
+    for i in 0..6 {
+      spd[(i, i)] += 1.0;
+    }
+    let l = cholesky(&spd);
+    assert_mat_close(&dot_product_transposed(&l, &l), &spd, 1e-12);
+  }
+
+  #[test]
+  fn test_cholesky_errors() {
+    let indefinite = Mat::from_vec(2, 2, vec![1.0, 2.0, 2.0, 1.0]);
+    assert_eq!(
+      try_cholesky(&indefinite).unwrap_err(),
+      MatError::NotPositiveDefinite {
+        operation: "Cholesky decomposition"
+      }
+    );
+    let asymmetric = Mat::from_vec(2, 2, vec![2.0, 1.0, 0.0, 2.0]);
+    assert_eq!(
+      try_cholesky(&asymmetric).unwrap_err(),
+      MatError::NotSymmetric {
+        operation: "Cholesky decomposition"
+      }
+    );
+    assert!(matches!(
+      try_cholesky(&Mat::new(2, 3)),
+      Err(MatError::NotSquare { .. })
+    ));
+  }
+
+  #[test]
+  fn test_lstsq_fits_a_line() {
+    // y = 2 x + 1 plus noise that cancels out, so the fit is exact
+    let xs = [0.0, 1.0, 2.0, 3.0, 4.0];
+    let noise = [0.1, -0.1, 0.0, -0.1, 0.1];
+    let mut a = Mat::new(5, 2);
+    let mut y = Mat::new(5, 1);
+    for i in 0..5 {
+      a[(i, 0)] = xs[i];
+      a[(i, 1)] = 1.0;
+      y[(i, 0)] = 2.0 * xs[i] + 1.0 + noise[i];
+    }
+    let fit = lstsq(&a, &y);
+    assert_mat_close(&fit, &Mat::from_vec(2, 1, vec![2.0, 1.0]), 1e-12);
+
+    // Square systems get the exact solution
+    let square = random_invertible(5);
+    let mut b = Mat::new(5, 2);
+    b.rand(-1.0, 1.0);
+    assert_mat_close(&lstsq(&square, &b), &solve(&square, &b), 1e-10);
+  }
+
+  #[test]
+  fn test_lstsq_matches_normal_equations() {
+    let mut a = Mat::new(30, 4);
+    a.rand(-1.0, 1.0);
+    let mut b = Mat::new(30, 1);
+    b.rand(-1.0, 1.0);
+    let at = a.transpose();
+    let expected = solve(&dot_product(&at, &a), &dot_product(&at, &b));
+    assert_mat_close(&lstsq(&a, &b), &expected, 1e-10);
+  }
+
+  #[test]
+  fn test_lstsq_errors() {
+    let dependent = Mat::from_vec(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+    let singular = MatError::Singular {
+      operation: "Least squares",
+    };
+    assert_eq!(
+      try_lstsq(&dependent, &Mat::new(3, 1)).unwrap_err(),
+      singular
+    );
+    assert_eq!(
+      try_lstsq(&Mat::new(2, 3), &Mat::new(2, 1)).unwrap_err(),
+      singular
+    );
+    assert_eq!(
+      try_lstsq(&Mat::new(3, 2), &Mat::new(2, 1)).unwrap_err(),
+      MatError::ShapeMismatch {
+        operation: "Least squares",
+        left: (3, 2),
+        right: (2, 1)
+      }
+    );
+  }
+
+  fn diagonal(values: &[f64]) -> Mat {
+    let mut mat = Mat::new(values.len(), values.len());
+    for (i, &value) in values.iter().enumerate() {
+      mat[(i, i)] = value;
+    }
+    return mat;
+  }
+
+  #[test]
+  fn test_symmetric_eigen() {
+    let a: Mat = Mat::from_vec(2, 2, vec![2.0, 1.0, 1.0, 2.0]);
+    let eigen = symmetric_eigen(&a);
+    assert!((eigen.values[0] - 3.0).abs() < 1e-12);
+    assert!((eigen.values[1] - 1.0).abs() < 1e-12);
+
+    let mut b = Mat::new(8, 8);
+    b.rand(-1.0, 1.0);
+    let symmetric = &b + &b.transpose();
+    let eigen = symmetric_eigen(&symmetric);
+    let v = &eigen.vectors;
+    // A = V diag(values) V^T with orthonormal V, values largest first
+    assert_mat_close(
+      &dot_product_transposed(&dot_product(v, &diagonal(&eigen.values)), v),
+      &symmetric,
+      1e-10,
+    );
+    assert_mat_close(&dot_product(&v.transpose(), v), &Mat::identity(8), 1e-10);
+    assert!(eigen.values.windows(2).all(|pair| pair[0] >= pair[1]));
+
+    assert_eq!(
+      try_symmetric_eigen(&Mat::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0])).unwrap_err(),
+      MatError::NotSymmetric {
+        operation: "Eigen decomposition"
+      }
+    );
+  }
+
+  #[test]
+  fn test_svd_reconstructs_matrix() {
+    for (m, n) in [(6, 4), (4, 6), (5, 5), (1, 3)] {
+      let mut a = Mat::new(m, n);
+      a.rand(-1.0, 1.0);
+      let decomposition = svd(&a);
+      let k = m.min(n);
+      let (u, v) = (&decomposition.u, &decomposition.v);
+      assert_eq!((u.rows(), u.cols(), v.rows(), v.cols()), (m, k, n, k));
+      let us = dot_product(u, &diagonal(&decomposition.singular_values));
+      assert_mat_close(&dot_product_transposed(&us, v), &a, 1e-10);
+      assert_mat_close(&dot_product(&u.transpose(), u), &Mat::identity(k), 1e-10);
+      assert_mat_close(&dot_product(&v.transpose(), v), &Mat::identity(k), 1e-10);
+      let sigmas = &decomposition.singular_values;
+      assert!(sigmas.windows(2).all(|pair| pair[0] >= pair[1]));
+      assert!(sigmas.iter().all(|&sigma| sigma >= 0.0));
+    }
+  }
+
+  #[test]
+  fn test_svd_of_known_matrix() {
+    // Singular values are the square roots of the eigenvalues of A^T A
+    let a: Mat = Mat::from_vec(2, 2, vec![3.0, 0.0, 4.0, 5.0]);
+    let decomposition = svd(&a);
+    assert!((decomposition.singular_values[0] - 45.0_f64.sqrt()).abs() < 1e-12);
+    assert!((decomposition.singular_values[1] - 5.0_f64.sqrt()).abs() < 1e-12);
+
+    // Rank one: one singular value is zero
+    let rank_one: Mat = Mat::from_vec(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+    let decomposition = svd(&rank_one);
+    assert!((decomposition.singular_values[0] - 70.0_f64.sqrt()).abs() < 1e-12);
+    assert!(decomposition.singular_values[1].abs() < 1e-12);
+  }
+
+  #[test]
+  fn test_svd_of_rank_deficient_matrix_is_orthonormal() {
+    let zero: Mat = Mat::new(3, 2);
+    let rank_one: Mat = Mat::from_vec(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+    let wide_rank_one: Mat = rank_one.transpose();
+    for a in [zero, rank_one, wide_rank_one] {
+      let decomposition = svd(&a);
+      let (u, v) = (&decomposition.u, &decomposition.v);
+      let k = a.rows().min(a.cols());
+      assert_mat_close(&dot_product(&u.transpose(), u), &Mat::identity(k), 1e-10);
+      assert_mat_close(&dot_product(&v.transpose(), v), &Mat::identity(k), 1e-10);
+      let us = dot_product(u, &diagonal(&decomposition.singular_values));
+      assert_mat_close(&dot_product_transposed(&us, v), &a, 1e-10);
+    }
+  }
+
+  #[test]
+  fn test_pca_finds_main_direction() {
+    // Points spread along (1, 1) with a little noise across it, shifted away from the origin
+    let mut data = Mat::new(200, 2);
+    let mut spread = Mat::new(200, 2);
+    spread.rand(-1.0, 1.0);
+    for i in 0..200 {
+      let (along, across) = (5.0 * spread[(i, 0)], 0.1 * spread[(i, 1)]);
+      data[(i, 0)] = 10.0 + along + across;
+      data[(i, 1)] = -3.0 + along - across;
+    }
+
+    let pca = data.pca(1);
+    assert_eq!((pca.projection.rows(), pca.projection.cols()), (200, 1));
+    assert_eq!((pca.components.rows(), pca.components.cols()), (2, 1));
+    let direction = 0.5_f64.sqrt();
+    assert!((pca.components[(0, 0)].abs() - direction).abs() < 1e-2);
+    assert!((pca.components[(1, 0)].abs() - direction).abs() < 1e-2);
+    assert!(pca.explained_variance_ratio[0] > 0.99);
+
+    // The explained variance is the sample variance of the projection
+    let projected = pca.projection.col(0);
+    let variance = projected.var() * 200.0 / 199.0;
+    assert!((pca.explained_variance[0] - variance).abs() < 1e-9);
+    assert!(projected.mean().abs() < 1e-9);
+    assert_mat_close(&pca.transform(&data), &pca.projection, 1e-12);
+  }
+
+  #[test]
+  fn test_pca_keeps_all_variance_with_every_component() {
+    let mut data = Mat::new(20, 4);
+    data.rand(-1.0, 1.0);
+    let pca = data.pca(4);
+    let total: f64 = pca.explained_variance_ratio.iter().sum();
+    assert!((total - 1.0).abs() < 1e-12);
+    assert!(pca
+      .explained_variance
+      .windows(2)
+      .all(|pair| pair[0] >= pair[1]));
+    // Rotating into all components keeps every distance, so the squared norms agree
+    let centered = &data - &pca.mean;
+    let norm = |mat: &Mat| mat.norm(Norm::Frobenius);
+    assert!((norm(&pca.projection) - norm(&centered)).abs() < 1e-10);
+  }
+
+  #[test]
+  fn test_pca_errors() {
+    let data = Mat::new(3, 5);
+    for k in [0, 4] {
+      assert_eq!(
+        data.try_pca(k).unwrap_err(),
+        MatError::InvalidComponentCount {
+          requested: k,
+          available: 3
+        }
+      );
+    }
+    let pca = data.pca(2);
+    assert!(matches!(
+      pca.try_transform(&Mat::new(2, 4)),
+      Err(MatError::ShapeMismatch { .. })
+    ));
+  }
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+  use nn::loss::loss::{BinaryCrossEntropy, CategoricalCrossEntropy, Huber, Loss, Mse};
+  use nn::matrix::{AsView, Mat};
+
+  fn row(values: &[f64]) -> Mat {
+    let mut mat = Mat::new(1, values.len());
+    for (j, value) in values.iter().enumerate() {
+      mat.set(0, j, *value);
+    }
+    return mat;
+  }
+
+  // Checks `derivative` against a central difference of `value`
+  fn assert_derivative_matches(loss: &dyn Loss, output: &[f64], target: &[f64]) {
+    let eps = 1e-6;
+    let analytic = loss.derivative(row(output).view(), row(target).view());
+    for j in 0..output.len() {
+      let mut plus = output.to_vec();
+      plus[j] += eps;
+      let mut minus = output.to_vec();
+      minus[j] -= eps;
+      let numeric = (loss.value(row(&plus).view(), row(target).view())
+        - loss.value(row(&minus).view(), row(target).view()))
+        / (2.0 * eps);
+      let value = analytic.get(0, j).unwrap();
+      assert!(
+        (value - numeric).abs() < 1e-6,
+        "Derivative {} is {} but finite difference gives {}",
+        j,
+        value,
+        numeric
+      );
+    }
+  }
+
+  #[test]
+  fn test_mse() {
+    let loss = Mse;
+    let value = loss.value(row(&[1.0, 2.0]).view(), row(&[0.0, 4.0]).view());
+    assert_eq!(value, 2.5);
+    assert_derivative_matches(&loss, &[0.3, -1.2], &[1.0, 0.5]);
+  }
+
+  #[test]
+  fn test_binary_cross_entropy() {
+    let loss = BinaryCrossEntropy;
+    let value = loss.value(row(&[0.5]).view(), row(&[1.0]).view());
+    assert!((value - 2f64.ln()).abs() < 1e-12);
+    assert_derivative_matches(&loss, &[0.2, 0.9], &[0.0, 1.0]);
+  }
+
+  #[test]
+  fn test_binary_cross_entropy_saturated_output_is_finite() {
+    let loss = BinaryCrossEntropy;
+    let value = loss.value(row(&[0.0, 1.0]).view(), row(&[1.0, 0.0]).view());
+    let derivative = loss.derivative(row(&[0.0, 1.0]).view(), row(&[1.0, 0.0]).view());
+    assert!(value.is_finite());
+    assert!(derivative.get(0, 0).unwrap().is_finite());
+    assert!(derivative.get(0, 1).unwrap().is_finite());
+  }
+
+  #[test]
+  fn test_categorical_cross_entropy() {
+    let loss = CategoricalCrossEntropy;
+    let value = loss.value(row(&[0.25, 0.5, 0.25]).view(), row(&[0.0, 1.0, 0.0]).view());
+    assert!((value - 2f64.ln()).abs() < 1e-12);
+    assert_derivative_matches(&loss, &[0.2, 0.3, 0.5], &[0.0, 0.0, 1.0]);
+  }
+
+  #[test]
+  fn test_huber() {
+    let loss = Huber::new(1.0);
+    // 0.5 * 0.5^2 inside delta, 1 * (3 - 0.5) outside of it
+    let value = loss.value(row(&[0.5, 3.0]).view(), row(&[0.0, 0.0]).view());
+    assert_eq!(value, (0.125 + 2.5) / 2.0);
+    assert_derivative_matches(&loss, &[0.5, 3.0, -2.0], &[0.0, 0.0, 0.0]);
+  }
+
+  #[test]
+  #[should_panic(expected = "Output and target dimensions must match.")]
+  fn test_loss_shape_mismatch() {
+    let _value = Mse.value(row(&[1.0, 2.0]).view(), row(&[1.0]).view());
+  }
+}
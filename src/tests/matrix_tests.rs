@@ -3,13 +3,20 @@ mod macros;
 #[cfg(test)]
 mod tests {
   use super::*;
-  use nn::matrix::{addition, dot_product, subtraction, Mat};
+  use nn::activation::activation::Activation;
+  use nn::matrix::{
+    add_scalar, addition, dot_product, dot_product_transposed, elementwise_div, hadamard, mat_copy,
+    mat_row, scale, subtraction, try_addition, try_dot_product, try_dot_product_transposed,
+    try_elementwise_div, try_hadamard, try_mat_copy, AsView, AsViewMut, Axis, Mat, MatError,
+    MatView, MatViewMut, Norm,
+  };
+  use std::ops::Bound;
 
   #[test]
   fn test_new_matrix() {
     let mat: Mat = Mat::new(2, 3);
-    assert_eq!(mat.rows, 2);
-    assert_eq!(mat.cols, 3);
+    assert_eq!(mat.rows(), 2);
+    assert_eq!(mat.cols(), 3);
   }
 
   #[test]
@@ -61,21 +68,37 @@ mod tests {
   }
 
   #[test]
-  #[should_panic(
-    expected = "Get failed! Supplied row Index 5 is out of bounds. Matrix has 3 rows."
-  )]
   fn test_mat_get_out_of_bounds_row() {
     let mat: Mat = Mat::new(3, 3);
-    let _value = mat.get(5, 2);
+    assert_eq!(mat.get(5, 2), None);
+    assert_eq!(
+      mat.try_get(5, 2).unwrap_err().to_string(),
+      "Get failed! Supplied row Index 5 is out of bounds. Matrix has 3 rows."
+    );
   }
 
   #[test]
-  #[should_panic(
-    expected = "Get failed! Supplied column Index 5 is out of bounds. Matrix has 3 columns."
-  )]
   fn test_mat_get_out_of_bounds_column() {
     let mat: Mat = Mat::new(3, 3);
-    let _value = mat.get(2, 5);
+    assert_eq!(mat.get(2, 5), None);
+    assert_eq!(
+      mat.try_get(2, 5).unwrap_err().to_string(),
+      "Get failed! Supplied column Index 5 is out of bounds. Matrix has 3 columns."
+    );
+  }
+
+  #[test]
+  fn test_get_past_the_edge_of_views() {
+    let mut mat = counting_matrix();
+    assert_eq!(mat.view().get(2, 3), Some(11.0));
+    assert_eq!(mat.view().get(3, 0), None);
+    assert_eq!(mat.view().get(0, 4), None);
+    assert_eq!(mat.view_mut().get(3, 0), None);
+    assert_eq!(mat.view_mut().get(0, 4), None);
+    let transposed = mat.t();
+    assert_eq!(transposed.get(3, 2), Some(11.0));
+    assert_eq!(transposed.get(2, 3), None);
+    assert_eq!(transposed.get(4, 0), None);
   }
 
   #[test]
@@ -95,8 +118,8 @@ mod tests {
     let mat2: Mat = Mat::new(3, 2);
     let result = dot_product(&mat1, &mat2);
 
-    assert_eq!(result.rows, 2);
-    assert_eq!(result.cols, 2);
+    assert_eq!(result.rows(), 2);
+    assert_eq!(result.cols(), 2);
   }
   #[test]
   fn test_addition() {
@@ -114,11 +137,11 @@ mod tests {
     expected.fill(5);
 
     // Compare the actual result with the expected result
-    assert_eq!(result.rows, expected.rows);
-    assert_eq!(result.cols, expected.cols);
+    assert_eq!(result.rows(), expected.rows());
+    assert_eq!(result.cols(), expected.cols());
 
-    for i in 0..to_usize!(result.rows) {
-      for j in 0..to_usize!(result.cols) {
+    for i in 0..to_usize!(result.rows()) {
+      for j in 0..to_usize!(result.cols()) {
         assert_eq!(result.get(i, j), expected.get(i, j));
       }
     }
@@ -140,11 +163,11 @@ mod tests {
     expected.fill(2);
 
     // Compare the actual result with the expected result
-    assert_eq!(result.rows, expected.rows);
-    assert_eq!(result.cols, expected.cols);
+    assert_eq!(result.rows(), expected.rows());
+    assert_eq!(result.cols(), expected.cols());
 
-    for i in 0..to_usize!(result.rows) {
-      for j in 0..to_usize!(result.cols) {
+    for i in 0..to_usize!(result.rows()) {
+      for j in 0..to_usize!(result.cols()) {
         assert_eq!(result.get(i, j), expected.get(i, j));
       }
     }
@@ -169,12 +192,12 @@ mod tests {
     expected.set(1, 1, 18);
 
     // Compare the actual result with the expected result
-    assert_eq!(result.rows, expected.rows);
-    assert_eq!(result.cols, expected.cols);
+    assert_eq!(result.rows(), expected.rows());
+    assert_eq!(result.cols(), expected.cols());
 
-    for i in 0..result.rows {
-      for j in 0..result.cols {
-        assert_eq!(safe_get!(result, i, j), safe_get!(expected, i, j));
+    for i in 0..result.rows() {
+      for j in 0..result.cols() {
+        assert_eq!(result[(i, j)], expected[(i, j)]);
       }
     }
   }
@@ -184,8 +207,8 @@ mod tests {
     let mat2: Mat = Mat::new(2, 2);
     let result = addition(&mat1, &mat2);
 
-    assert_eq!(result.rows, 2);
-    assert_eq!(result.cols, 2);
+    assert_eq!(result.rows(), 2);
+    assert_eq!(result.cols(), 2);
   }
 
   #[test]
@@ -201,7 +224,7 @@ mod tests {
     let rows = 3;
     let cols = 3;
     // Create a matrix
-    let mat = Mat::new(rows, cols);
+    let mut mat = Mat::new(rows, cols);
 
     let low = 0.0;
     let high = 9.0;
@@ -228,4 +251,803 @@ mod tests {
     // This should panic with "Multiplication overflow"
     let _mat: Mat = Mat::new(usize::MAX, 2);
   }
+
+  #[test]
+  fn test_clone_owns_its_buffer() {
+    let mut mat = Mat::new(2, 2);
+    mat.fill(1);
+    let copy = mat.clone();
+    mat.set(0, 0, 7);
+
+    assert_eq!(copy.get(0, 0), Some(1.0));
+    assert_eq!(mat.get(0, 0), Some(7.0));
+  }
+
+  #[test]
+  fn test_from_vec() {
+    let mat = Mat::from_vec(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(mat.get(0, 2), Some(3.0));
+    assert_eq!(mat.get(1, 0), Some(4.0));
+  }
+
+  #[test]
+  #[should_panic(expected = "A (2x3) matrix needs 6 elements. Got 5.")]
+  fn test_from_vec_wrong_length() {
+    let _mat = Mat::from_vec(2, 3, vec![0.0; 5]);
+  }
+
+  #[test]
+  fn test_strided_view() {
+    // Two inputs and one output per row, like a training table
+    let data = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    let inputs = MatView::new(&data, 3, 2, 3);
+    let outputs = MatView::new(&data[2..], 3, 1, 3);
+
+    assert_eq!(inputs.get(1, 0), Some(3.0));
+    assert_eq!(inputs.get(2, 1), Some(7.0));
+    assert_eq!(outputs.get(2, 0), Some(8.0));
+
+    let row = mat_row(&inputs, 2);
+    assert_eq!((row.rows(), row.cols()), (1, 2));
+    assert_eq!(row.get(0, 1), Some(7.0));
+
+    let mut copy = Mat::new(1, 2);
+    mat_copy(&mut copy, &row);
+    assert_eq!(copy.get(0, 0), Some(6.0));
+    assert_eq!(inputs.to_mat().view().get(2, 1), Some(7.0));
+  }
+
+  #[test]
+  fn test_view_mut_writes_through_stride() {
+    let mut data = vec![0.0; 6];
+    let mut view = MatViewMut::new(&mut data[1..], 2, 1, 3);
+    view.set(0, 0, 1);
+    view.set(1, 0, 2);
+
+    assert_eq!(data, vec![0.0, 1.0, 0.0, 0.0, 2.0, 0.0]);
+  }
+
+  #[test]
+  #[should_panic(
+    expected = "A (3x2) view with stride 3 needs 8 elements, but the buffer only has 7."
+  )]
+  fn test_view_out_of_buffer() {
+    let data = vec![0.0; 7];
+    let _view = MatView::new(&data, 3, 2, 3);
+  }
+
+  // 3x4 buffer holding 0..12 row by row, windows into it are not contiguous
+  fn table() -> Vec<f64> {
+    return (0..12).map(|value| value as f64).collect();
+  }
+
+  #[test]
+  fn test_addition_and_subtraction_on_strided_views() {
+    let data = table();
+    let left = MatView::new(&data, 3, 2, 4);
+    let right = MatView::new(&data[2..], 3, 2, 4);
+
+    let sum = addition(&left, &right);
+    let diff = subtraction(&right, &left);
+
+    for i in 0..3 {
+      for j in 0..2 {
+        let expected = (4 * i + j) as f64 + (4 * i + j + 2) as f64;
+        assert_eq!(sum.get(i, j), Some(expected));
+        assert_eq!(diff.get(i, j), Some(2.0));
+      }
+    }
+  }
+
+  #[test]
+  fn test_dot_product_on_strided_view() {
+    let data = table();
+    // [[1, 2], [5, 6], [9, 10]]
+    let view = MatView::new(&data[1..], 3, 2, 4);
+    let mut ones = Mat::new(2, 1);
+    ones.fill(1);
+
+    let result = dot_product(&view, &ones);
+
+    assert_eq!(result.get(0, 0), Some(3.0));
+    assert_eq!(result.get(1, 0), Some(11.0));
+    assert_eq!(result.get(2, 0), Some(19.0));
+  }
+
+  #[test]
+  fn test_mat_copy_into_strided_view() {
+    let mut data = table();
+    let mut source = Mat::new(3, 1);
+    source.fill(-1);
+
+    mat_copy(&mut MatViewMut::new(&mut data[3..], 3, 1, 4), &source);
+
+    for (index, value) in data.iter().enumerate() {
+      let expected = if index % 4 == 3 { -1.0 } else { index as f64 };
+      assert_eq!(*value, expected, "Element {} was changed", index);
+    }
+  }
+
+  #[test]
+  fn test_fill_and_rand_stay_inside_strided_view() {
+    let mut data = table();
+    {
+      let mut view = MatViewMut::new(&mut data[1..], 2, 2, 4);
+      view.fill(100);
+    }
+    assert_eq!(data[1..3], [100.0, 100.0]);
+    assert_eq!(data[5..7], [100.0, 100.0]);
+    assert_eq!(data[3..5], [3.0, 4.0]);
+    assert_eq!(data[7..], [7.0, 8.0, 9.0, 10.0, 11.0]);
+
+    let mut data = table();
+    MatViewMut::new(&mut data[1..], 2, 2, 4).rand(-1.0, 0.0);
+    for (index, value) in data.iter().enumerate() {
+      if [1, 2, 5, 6].contains(&index) {
+        assert!(*value <= 0.0, "Element {} was not randomized", index);
+      } else {
+        assert_eq!(*value, index as f64, "Element {} was changed", index);
+      }
+    }
+  }
+
+  #[test]
+  fn test_set_on_view_mut_of_mat() {
+    let mut mat = Mat::new(2, 3);
+    mat.view_mut().set(1, 2, 5);
+    assert_eq!(mat.get(1, 2), Some(5.0));
+    assert_eq!(mat.view().get(1, 2), Some(5.0));
+  }
+
+  #[test]
+  fn test_try_new_errors() {
+    assert_eq!(
+      Mat::try_new(0, 3),
+      Err(MatError::ZeroDimension { rows: 0, cols: 3 })
+    );
+    assert_eq!(Mat::try_new(usize::MAX, 2), Err(MatError::Overflow));
+    assert_eq!(
+      Mat::try_from_vec(2, 2, vec![0.0; 3]),
+      Err(MatError::LengthMismatch {
+        rows: 2,
+        cols: 2,
+        len: 3
+      })
+    );
+    assert!(Mat::try_new(2, 3).is_ok());
+  }
+
+  #[test]
+  fn test_try_get_and_try_set_out_of_bounds() {
+    let mut mat = Mat::new(2, 2);
+    let error = mat.try_get(2, 0).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "Get failed! Supplied row Index 2 is out of bounds. Matrix has 2 rows."
+    );
+    assert!(mat.try_set(0, 5, 1.0).is_err());
+    assert_eq!(mat.try_set(1, 1, 1.0), Ok(()));
+    assert_eq!(mat.try_get(1, 1), Ok(1.0));
+  }
+
+  #[test]
+  fn test_try_operations_reject_shape_mismatch() {
+    let mat1 = Mat::new(2, 2);
+    let mat2 = Mat::new(2, 3);
+
+    assert_eq!(
+      try_addition(&mat1, &mat2),
+      Err(MatError::ShapeMismatch {
+        operation: "Addition",
+        left: (2, 2),
+        right: (2, 3)
+      })
+    );
+    assert!(try_dot_product(&mat2, &mat1).is_err());
+    assert!(try_dot_product(&mat1, &mat2).is_ok());
+    assert!(try_mat_copy(&mut Mat::new(3, 3), &mat1).is_err());
+  }
+
+  #[test]
+  fn test_try_new_view_errors() {
+    let data = vec![0.0; 7];
+    assert_eq!(
+      MatView::try_new(&data, 2, 4, 3).unwrap_err(),
+      MatError::InvalidStride { stride: 3, cols: 4 }
+    );
+    assert!(matches!(
+      MatView::try_new(&data, 3, 2, 3),
+      Err(MatError::BufferTooSmall { needed: 8, .. })
+    ));
+    assert!(MatView::try_new(&data, 3, 1, 3).is_ok());
+  }
+
+  #[test]
+  fn test_operators_match_functions() {
+    let a = Mat::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    let b = Mat::from_vec(2, 2, vec![0.5, -1.0, 2.0, 0.0]);
+
+    assert_eq!(&a + &b, addition(&a, &b));
+    assert_eq!(&a - &b, subtraction(&a, &b));
+    assert_eq!(&a * &b, dot_product(&a, &b));
+    assert_eq!(a.clone() + b.clone(), addition(&a, &b));
+    assert_eq!(a.clone() * &b, dot_product(&a, &b));
+    assert_eq!(-&a, Mat::from_vec(2, 2, vec![-1.0, -2.0, -3.0, -4.0]));
+    assert_eq!(&a * 2.0, Mat::from_vec(2, 2, vec![2.0, 4.0, 6.0, 8.0]));
+    assert_eq!(2.0 * &a, &a * 2.0);
+    assert_eq!(&a / 2.0, Mat::from_vec(2, 2, vec![0.5, 1.0, 1.5, 2.0]));
+  }
+
+  #[test]
+  fn test_assign_operators() {
+    let a = Mat::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    let mut mat = a.clone();
+
+    mat += &a;
+    assert_eq!(mat, &a * 2.0);
+    mat -= a.clone();
+    assert_eq!(mat, a);
+    mat *= 3.0;
+    mat /= 3.0;
+    assert_eq!(mat, a);
+
+    // `*=` with a matrix is the matrix product and can change the shape
+    mat *= Mat::from_vec(2, 1, vec![1.0, 1.0]);
+    assert_eq!(mat, Mat::from_vec(2, 1, vec![3.0, 7.0]));
+  }
+
+  #[test]
+  fn test_index_honours_stride() {
+    let mut data = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    assert_eq!(MatView::new(&data, 2, 2, 3)[(1, 1)], 4.0);
+
+    let mut view = MatViewMut::new(&mut data, 2, 2, 3);
+    view[(1, 0)] = 9.0;
+    assert_eq!(data[3], 9.0);
+
+    let mut mat = Mat::new(2, 3);
+    mat[(1, 2)] = 7.0;
+    mat[(1, 2)] += 1.0;
+    assert_eq!(mat.get(1, 2), Some(8.0));
+  }
+
+  #[test]
+  #[should_panic(expected = "Addition failed! Matrix dimensions do not fit.")]
+  fn test_add_operator_rejects_shape_mismatch() {
+    let _sum = Mat::new(2, 2) + Mat::new(2, 3);
+  }
+
+  #[test]
+  #[should_panic(expected = "Supplied column Index 3 is out of bounds.")]
+  fn test_index_out_of_bounds() {
+    let mat = Mat::new(2, 3);
+    let _value = mat[(0, 3)];
+  }
+
+  // Plain triple loop to check the blocked product against
+  fn naive_product(a: &Mat, b: &Mat) -> Mat {
+    let mut result = Mat::new(a.rows(), b.cols());
+    for i in 0..a.rows() {
+      for j in 0..b.cols() {
+        for k in 0..a.cols() {
+          result[(i, j)] += a[(i, k)] * b[(k, j)];
+        }
+      }
+    }
+    return result;
+  }
+
+  #[test]
+  fn test_dot_product_matches_naive_product() {
+    // Covers the unpacked path, several k blocks and a column count that is not a multiple of 4
+    for (m, k, n) in [(3, 5, 6), (9, 300, 7), (17, 520, 130)] {
+      let mut a = Mat::new(m, k);
+      let mut b = Mat::new(k, n);
+      a.rand(-1.0, 1.0);
+      b.rand(-1.0, 1.0);
+
+      let result = dot_product(&a, &b);
+      let expected = naive_product(&a, &b);
+      for i in 0..m {
+        for j in 0..n {
+          assert!(
+            (result[(i, j)] - expected[(i, j)]).abs() < 1e-9,
+            "({}x{}x{}) differs at ({}, {})",
+            m,
+            k,
+            n,
+            i,
+            j
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_large_elementwise_kernels() {
+    // Big enough to be split into row blocks when the `parallel` feature is on
+    let (rows, cols) = (600, 500);
+    let mut a = Mat::new(rows, cols);
+    let mut b = Mat::new(rows, cols);
+    a.rand(-1.0, 1.0);
+    b.rand(-1.0, 1.0);
+
+    let sum = &a + &b;
+    let difference = &a - &b;
+    let mut softmax = a.clone();
+    softmax.apply(Activation::Softmax);
+    for i in 0..rows {
+      let mut row_sum = 0.0;
+      for j in 0..cols {
+        assert_eq!(sum[(i, j)], a[(i, j)] + b[(i, j)]);
+        assert_eq!(difference[(i, j)], a[(i, j)] - b[(i, j)]);
+        row_sum += softmax[(i, j)];
+      }
+      assert!(
+        (row_sum - 1.0).abs() < 1e-12,
+        "Row {} sums to {}",
+        i,
+        row_sum
+      );
+    }
+  }
+
+  #[test]
+  fn test_f32_matrix_operations() {
+    let a = Mat::from_vec(2, 2, vec![1.0f32, 2.0, 3.0, 4.0]);
+    let mut b = Mat::<f32>::zeros(2, 2);
+    b.fill(1.0f32);
+    b[(1, 1)] = -2.0;
+
+    assert_eq!(&a * &a, Mat::from_vec(2, 2, vec![7.0f32, 10.0, 15.0, 22.0]));
+    assert_eq!(&a + &b, Mat::from_vec(2, 2, vec![2.0f32, 3.0, 4.0, 2.0]));
+    assert_eq!(&a - &b, Mat::from_vec(2, 2, vec![0.0f32, 1.0, 2.0, 6.0]));
+    assert_eq!(2.0f32 * &a, &a + &a);
+
+    b.apply(Activation::Relu);
+    assert_eq!(b.get(1, 1), Some(0.0f32));
+    assert_eq!(mat_row(&a, 1).get(0, 1), Some(4.0f32));
+  }
+
+  // 3x4 matrix holding 0..12 row by row
+  fn counting_matrix() -> Mat {
+    return Mat::from_vec(3, 4, (0..12).map(|x| x as f64).collect());
+  }
+
+  #[test]
+  fn test_col_and_slice_share_the_buffer() {
+    let mat = counting_matrix();
+
+    let col = mat.col(2);
+    assert_eq!((col.rows(), col.cols(), col.stride()), (3, 1, 4));
+    assert_eq!(col.to_mat(), Mat::from_vec(3, 1, vec![2.0, 6.0, 10.0]));
+
+    let block = mat.slice(1..3, 1..=2);
+    assert_eq!((block.rows(), block.cols(), block.stride()), (2, 2, 4));
+    assert_eq!(
+      block.to_mat(),
+      Mat::from_vec(2, 2, vec![5.0, 6.0, 9.0, 10.0])
+    );
+    assert_eq!(mat.slice(.., 3..).to_mat(), mat.col(3).to_mat());
+    assert_eq!(mat.submatrix(1, 1, 2, 2).to_mat(), block.to_mat());
+
+    // Views of views keep addressing the original buffer
+    assert_eq!(block.col(1)[(1, 0)], 10.0);
+    assert_eq!(block.slice(1.., ..)[(0, 0)], 9.0);
+  }
+
+  #[test]
+  fn test_slice_rejects_out_of_range() {
+    let mat = counting_matrix();
+    assert_eq!(
+      mat.try_slice(1..4, ..).unwrap_err(),
+      MatError::OutOfBounds {
+        operation: "Submatrix",
+        row: 3,
+        col: 3,
+        rows: 3,
+        cols: 4
+      }
+    );
+    assert_eq!(
+      mat.try_slice(2..2, ..).unwrap_err(),
+      MatError::ZeroDimension { rows: 0, cols: 4 }
+    );
+    assert!(mat.try_col(4).is_err());
+    assert!(mat.try_submatrix(2, 3, 1, 2).is_err());
+    // Bounds one past usize::MAX must not overflow
+    assert_eq!(
+      mat.try_slice(..=usize::MAX, ..).unwrap_err(),
+      MatError::Overflow
+    );
+    assert_eq!(
+      mat
+        .try_slice(.., (Bound::Excluded(usize::MAX), Bound::Unbounded))
+        .unwrap_err(),
+      MatError::Overflow
+    );
+  }
+
+  #[test]
+  fn test_submatrix_mut_writes_through() {
+    let mut mat = counting_matrix();
+    let mut block = mat.submatrix_mut(1, 2, 2, 2);
+    block.fill(-1.0);
+    block[(0, 1)] = 7.0;
+    mat.slice_mut(0..1, ..1).set(0, 0, 42.0);
+
+    let expected = vec![
+      42.0, 1.0, 2.0, 3.0, //
+      4.0, 5.0, -1.0, 7.0, //
+      8.0, 9.0, -1.0, -1.0,
+    ];
+    assert_eq!(mat, Mat::from_vec(3, 4, expected));
+  }
+
+  #[test]
+  fn test_reshape() {
+    let mat = counting_matrix();
+    let reshaped = mat.reshape(2, 6);
+    assert_eq!(
+      reshaped.to_mat(),
+      Mat::from_vec(2, 6, (0..12).map(|x| x as f64).collect())
+    );
+    assert_eq!(mat_row(&mat, 1).reshape(4, 1)[(3, 0)], 7.0);
+
+    assert_eq!(
+      mat.try_reshape(5, 2).unwrap_err(),
+      MatError::LengthMismatch {
+        rows: 5,
+        cols: 2,
+        len: 12
+      }
+    );
+    assert_eq!(
+      mat.slice(.., 1..3).try_reshape(1, 6).unwrap_err(),
+      MatError::NotContiguous {
+        rows: 3,
+        cols: 2,
+        stride: 4
+      }
+    );
+  }
+
+  #[test]
+  fn test_transpose_view_and_copy_agree() {
+    let mat = counting_matrix();
+    let view = mat.t();
+    let copy = mat.transpose();
+    assert_eq!((view.rows, view.cols), (4, 3));
+    assert_eq!((copy.rows(), copy.cols()), (4, 3));
+    for i in 0..4 {
+      for j in 0..3 {
+        assert_eq!(view[(i, j)], mat[(j, i)]);
+        assert_eq!(copy[(i, j)], mat[(j, i)]);
+      }
+    }
+    assert_eq!(copy.transpose(), mat);
+    assert_eq!(view.t().to_mat(), mat);
+    assert_eq!(
+      mat.slice(1.., ..2).transpose(),
+      Mat::from_vec(2, 2, vec![4.0, 8.0, 5.0, 9.0])
+    );
+  }
+
+  #[test]
+  fn test_dot_product_transposed() {
+    for (m, k, n) in [(1, 3, 4), (5, 7, 3), (20, 33, 17)] {
+      let mut a = Mat::new(m, k);
+      let mut b = Mat::new(n, k);
+      a.rand(-1.0, 1.0);
+      b.rand(-1.0, 1.0);
+      let expected = dot_product(&a, &b.transpose());
+      let result = dot_product_transposed(&a, &b);
+      for i in 0..m {
+        for j in 0..n {
+          assert!((result[(i, j)] - expected[(i, j)]).abs() < 1e-12);
+        }
+      }
+    }
+
+    // Strided operands, e.g. a block of a wider weight matrix
+    let mat = counting_matrix();
+    let left = mat.slice(..2, 1..3);
+    let right = mat.slice(1.., ..2);
+    assert_eq!(
+      dot_product_transposed(&left, &right),
+      dot_product(&left, &right.transpose())
+    );
+
+    assert_eq!(
+      try_dot_product_transposed(&Mat::new(2, 3), &Mat::new(2, 4)).unwrap_err(),
+      MatError::ShapeMismatch {
+        operation: "Dot product",
+        left: (2, 3),
+        right: (4, 2)
+      }
+    );
+  }
+
+  #[test]
+  fn test_broadcast_row_and_column() {
+    let batch = counting_matrix();
+    let bias = Mat::from_vec(1, 4, vec![10.0, 20.0, 30.0, 40.0]);
+    let column = Mat::from_vec(3, 1, vec![1.0, 2.0, 3.0]);
+
+    let sum = &batch + &bias;
+    let difference = subtraction(&batch, &column);
+    let product = hadamard(&column, &batch);
+    let quotient = elementwise_div(&batch, &bias);
+    for i in 0..3 {
+      for j in 0..4 {
+        assert_eq!(sum[(i, j)], batch[(i, j)] + bias[(0, j)]);
+        assert_eq!(difference[(i, j)], batch[(i, j)] - column[(i, 0)]);
+        assert_eq!(product[(i, j)], column[(i, 0)] * batch[(i, j)]);
+        assert_eq!(quotient[(i, j)], batch[(i, j)] / bias[(0, j)]);
+      }
+    }
+
+    // A column and a row broadcast against each other into a full matrix
+    let outer = addition(&column, &bias);
+    assert_eq!((outer.rows(), outer.cols()), (3, 4));
+    assert_eq!(outer[(2, 1)], 23.0);
+    assert_eq!(
+      &batch - &Mat::from_vec(1, 1, vec![1.0]),
+      &batch + &Mat::from_vec(1, 1, vec![-1.0])
+    );
+
+    // Broadcast operands may be strided views
+    let wide = counting_matrix();
+    assert_eq!(
+      addition(&batch.slice(.., 1..3), &mat_row(&wide, 2).slice(.., 2..)),
+      Mat::from_vec(3, 2, vec![11.0, 13.0, 15.0, 17.0, 19.0, 21.0])
+    );
+
+    // Large enough for the parallel kernels, with a column operand
+    let mut big = Mat::new(700, 400);
+    big.rand(-1.0, 1.0);
+    let mut scale = Mat::new(700, 1);
+    scale.rand(1.0, 2.0);
+    let scaled = hadamard(&big, &scale);
+    for i in (0..700).step_by(97) {
+      for j in (0..400).step_by(31) {
+        assert_eq!(scaled[(i, j)], big[(i, j)] * scale[(i, 0)]);
+      }
+    }
+  }
+
+  #[test]
+  fn test_broadcast_assign() {
+    let mut batch = counting_matrix();
+    batch += &Mat::from_vec(1, 4, vec![1.0; 4]);
+    batch -= &Mat::from_vec(3, 1, vec![1.0, 2.0, 3.0]);
+    assert_eq!(
+      batch,
+      Mat::from_vec(
+        3,
+        4,
+        vec![0.0, 1.0, 2.0, 3.0, 3.0, 4.0, 5.0, 6.0, 6.0, 7.0, 8.0, 9.0]
+      )
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "Addition failed! Matrix dimensions do not fit. Got Mat1: (1x4)")]
+  fn test_broadcast_assign_cannot_grow() {
+    let mut bias = Mat::new(1, 4);
+    bias += &counting_matrix();
+  }
+
+  #[test]
+  fn test_broadcast_rejects_incompatible_shapes() {
+    let batch = counting_matrix();
+    for other in [Mat::new(1, 3), Mat::new(2, 4), Mat::new(2, 1)] {
+      assert_eq!(
+        try_addition(&batch, &other).unwrap_err(),
+        MatError::ShapeMismatch {
+          operation: "Addition",
+          left: (3, 4),
+          right: (other.rows(), other.cols())
+        }
+      );
+    }
+    assert!(try_elementwise_div(&Mat::new(2, 3), &Mat::new(3, 2)).is_err());
+    assert!(try_hadamard(&Mat::new(2, 1), &Mat::new(3, 1)).is_err());
+  }
+
+  #[test]
+  fn test_map_and_zip_map() {
+    let mat = counting_matrix();
+    let squared = mat.map(|x| x * x);
+    let mut in_place = mat.clone();
+    in_place.map_inplace(|x| x * x);
+    assert_eq!(squared, in_place);
+    assert_eq!(squared[(2, 3)], 121.0);
+
+    let bias = Mat::from_vec(1, 4, vec![1.0, 2.0, 3.0, 4.0]);
+    let zipped = mat.zip_map(&bias, |x, y| x.max(y));
+    assert_eq!(
+      zipped,
+      Mat::from_vec(
+        3,
+        4,
+        vec![1.0, 2.0, 3.0, 4.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0]
+      )
+    );
+    assert_eq!(
+      mat.try_zip_map(&Mat::new(2, 4), |x, _| x).unwrap_err(),
+      MatError::ShapeMismatch {
+        operation: "Zip map",
+        left: (3, 4),
+        right: (2, 4)
+      }
+    );
+  }
+
+  #[test]
+  fn test_map_inplace_honours_stride() {
+    let mut mat = counting_matrix();
+    mat.slice_mut(1.., 1..3).map_inplace(|x| -x);
+    let expected = vec![
+      0.0, 1.0, 2.0, 3.0, //
+      4.0, -5.0, -6.0, 7.0, //
+      8.0, -9.0, -10.0, 11.0,
+    ];
+    assert_eq!(mat, Mat::from_vec(3, 4, expected));
+    assert_eq!(
+      mat.col(1).map(|x| x + 1.0),
+      Mat::from_vec(3, 1, vec![2.0, -4.0, -8.0])
+    );
+    assert_eq!(
+      mat.col(2).zip_map(&mat.col(1), |x, y| x - y).get(2, 0),
+      Some(-1.0)
+    );
+  }
+
+  #[test]
+  fn test_scalar_functions_and_operators() {
+    let mat = counting_matrix();
+    let shifted = add_scalar(&mat, 0.5);
+    let scaled = scale(&mat.slice(.., 2..), 3.0);
+    assert_eq!(shifted[(1, 2)], 6.5);
+    assert_eq!(
+      scaled,
+      Mat::from_vec(3, 2, vec![6.0, 9.0, 18.0, 21.0, 30.0, 33.0])
+    );
+
+    assert_eq!(&mat + 0.5, shifted);
+    assert_eq!(&mat - 0.5, add_scalar(&mat, -0.5));
+    assert_eq!(mat.clone() + 1.0 - 1.0, mat);
+    let mut shifted_back = shifted.clone();
+    shifted_back -= 0.5;
+    assert_eq!(shifted_back, mat);
+    shifted_back += 2.0;
+    assert_eq!(shifted_back, &mat + 2.0);
+  }
+
+  fn assert_close(actual: f64, expected: f64) {
+    assert!(
+      (actual - expected).abs() < 1e-12,
+      "Expected {}, got {}",
+      expected,
+      actual
+    );
+  }
+
+  #[test]
+  fn test_sums_and_means() {
+    let mat = counting_matrix();
+    assert_eq!(mat.sum(), 66.0);
+    assert_eq!(mat.mean(), 5.5);
+    assert_eq!(
+      mat.sum_axis(Axis::Row),
+      Mat::from_vec(3, 1, vec![6.0, 22.0, 38.0])
+    );
+    assert_eq!(
+      mat.sum_axis(Axis::Column),
+      Mat::from_vec(1, 4, vec![12.0, 15.0, 18.0, 21.0])
+    );
+    assert_eq!(
+      mat.mean_axis(Axis::Row),
+      Mat::from_vec(3, 1, vec![1.5, 5.5, 9.5])
+    );
+    assert_eq!(
+      mat.mean_axis(Axis::Column),
+      Mat::from_vec(1, 4, vec![4.0, 5.0, 6.0, 7.0])
+    );
+
+    // Only the elements inside a strided view count
+    let block = mat.slice(1.., 1..3);
+    assert_eq!(block.sum(), 30.0);
+    assert_eq!(
+      block.sum_axis(Axis::Column),
+      Mat::from_vec(1, 2, vec![14.0, 16.0])
+    );
+    assert_eq!(block.mean(), 7.5);
+  }
+
+  #[test]
+  fn test_spread_and_extremes() {
+    let mat = counting_matrix();
+    // Population variance of 0..12 is (12^2 - 1) / 12
+    assert_close(mat.var(), 143.0 / 12.0);
+    assert_close(mat.std(), (143.0_f64 / 12.0).sqrt());
+    assert_eq!(Mat::from_vec(1, 3, vec![2.0, 2.0, 2.0]).var(), 0.0);
+
+    assert_eq!(mat.min(), 0.0);
+    assert_eq!(mat.max(), 11.0);
+    assert_eq!(mat.slice(..2, 1..3).max(), 6.0);
+    let with_nan = Mat::from_vec(1, 3, vec![f64::NAN, -1.0, 4.0]);
+    assert_eq!((with_nan.min(), with_nan.max()), (-1.0, 4.0));
+  }
+
+  #[test]
+  fn test_min_max_axis() {
+    let mat = Mat::from_vec(2, 3, vec![4.0, -2.0, 7.0, 1.0, 5.0, f64::NAN]);
+    assert_eq!(mat.max_axis(Axis::Row), Mat::from_vec(2, 1, vec![7.0, 5.0]));
+    assert_eq!(
+      mat.min_axis(Axis::Row),
+      Mat::from_vec(2, 1, vec![-2.0, 1.0])
+    );
+    assert_eq!(
+      mat.max_axis(Axis::Column),
+      Mat::from_vec(1, 3, vec![4.0, 5.0, 7.0])
+    );
+    assert_eq!(
+      mat.min_axis(Axis::Column),
+      Mat::from_vec(1, 3, vec![1.0, -2.0, 7.0])
+    );
+
+    // Only the elements inside a strided view count
+    let counting = counting_matrix();
+    let block = counting.slice(1.., 1..3);
+    assert_eq!(
+      block.max_axis(Axis::Row),
+      Mat::from_vec(2, 1, vec![6.0, 10.0])
+    );
+    assert_eq!(
+      block.min_axis(Axis::Column),
+      Mat::from_vec(1, 2, vec![5.0, 6.0])
+    );
+  }
+
+  #[test]
+  fn test_argmax_axis() {
+    let scores = Mat::from_vec(3, 3, vec![0.1, 0.7, 0.2, 0.5, 0.2, 0.5, -1.0, -3.0, 0.0]);
+    assert_eq!(scores.argmax_axis(Axis::Row), vec![1, 0, 2]);
+    assert_eq!(scores.argmax_axis(Axis::Column), vec![1, 0, 1]);
+    assert_eq!(scores.slice(1.., 1..).argmax_axis(Axis::Row), vec![1, 1]);
+    assert_eq!(counting_matrix().argmax_axis(Axis::Column), vec![2; 4]);
+  }
+
+  #[test]
+  fn test_norms() {
+    let mat = Mat::from_vec(2, 2, vec![3.0, -4.0, 0.0, 12.0]);
+    // Largest absolute column sum, largest absolute row sum and largest singular value
+    assert_eq!(mat.norm(Norm::L1), 16.0);
+    assert_eq!(mat.norm(Norm::Infinity), 12.0);
+    // The eigenvalues of mat^T mat are (169 +- sqrt(23377)) / 2
+    assert_close(
+      mat.norm(Norm::L2),
+      ((169.0 + 23377.0_f64.sqrt()) / 2.0).sqrt(),
+    );
+    assert_eq!(mat.norm(Norm::EntrywiseL1), 19.0);
+    assert_eq!(mat.norm(Norm::Frobenius), 13.0);
+    assert_eq!(mat.norm(Norm::Max), 12.0);
+    assert_eq!(mat.col(1).norm(Norm::Max), 12.0);
+    assert_eq!(mat_row(&mat, 0).norm(Norm::Frobenius), 5.0);
+    assert_eq!(mat_row(&mat, 0).norm(Norm::EntrywiseL1), 7.0);
+    // On a column the induced norms are the vector norms
+    assert_eq!(mat.col(1).norm(Norm::L1), 16.0);
+    assert_eq!(mat.col(1).norm(Norm::Infinity), 12.0);
+    assert_close(mat.col(1).norm(Norm::L2), 160.0_f64.sqrt());
+    // A row is a 1xn matrix, so L1 and infinity swap roles
+    assert_eq!(mat_row(&mat, 0).norm(Norm::L1), 4.0);
+    assert_eq!(mat_row(&mat, 0).norm(Norm::Infinity), 7.0);
+
+    let small = Mat::from_vec(1, 2, vec![3.0f32, -4.0]);
+    assert_eq!(small.norm(Norm::Frobenius), 5.0f32);
+    assert_eq!(small.sum(), -1.0f32);
+  }
 }
@@ -1,18 +1,20 @@
-#[path = "../network.rs"]
-mod network;
+#[path = "fixtures.rs"]
+mod fixtures;
 #[cfg(test)]
 mod tests {
-  use crate::network::network::Network as NN;
+  use super::fixtures::fixtures::{init_deterministic, xor_data, xor_network};
+  use nn::activation::activation::Activation;
+  use nn::loss::loss::{BinaryCrossEntropy, CategoricalCrossEntropy, Huber, Mse};
+  use nn::matrix::{mat_row, Mat, MatError, MatView};
+  use nn::network::network::{Network as NN, NnError};
 
   #[test]
   fn test_network_drop() {
     // Create a mock Network instance
     let network = NN::new(&[2, 3, 1]);
 
-    // Call the drop function explicitly
-    drop(&network);
-
-    // No assertions are required as the drop function is responsible for resource cleanup
+    // Dropping frees every layer, there is nothing left to clean up by hand
+    drop(network);
   }
   #[test]
   fn test_network_new() {
@@ -22,10 +24,10 @@ mod tests {
     // Assert that the network count is set correctly
     assert_eq!(network.count, 2);
 
-    // Assert that the network weights, bias, and activations are not null
-    assert!(!network.weights.is_null());
-    assert!(!network.bias.is_null());
-    assert!(!network.activations.is_null());
+    // Assert that the network owns one weights and bias matrix per layer plus the input layer
+    assert_eq!(network.get_weights().len(), 2);
+    assert_eq!(network.get_bias().len(), 2);
+    assert_eq!(network.get_activations().len(), 3);
   }
 
   #[test]
@@ -36,12 +38,6 @@ mod tests {
     // Assert that the network count is set correctly
     assert_eq!(network.count, arch.len() - 1);
 
-    // Assert that the weights, bias, and activations pointers are not null
-    assert!(!network.weights.is_null());
-    assert!(!network.bias.is_null());
-    assert!(!network.activations.is_null());
-
-    // Convert the pointers back to Vec to perform further assertions
     let weights_mat_array = network.get_weights();
     let bias_mat_array = network.get_bias();
     let activations_mat_array = network.get_activations();
@@ -54,16 +50,427 @@ mod tests {
     // Assert that each matrix in weights, bias, and activations has the expected dimensions
     for i in 0..network.count {
       let weights_matrix = &weights_mat_array[i];
-      assert_eq!(weights_matrix.rows, arch[i]);
-      assert_eq!(weights_matrix.cols, arch[i + 1]);
+      assert_eq!(weights_matrix.rows(), arch[i]);
+      assert_eq!(weights_matrix.cols(), arch[i + 1]);
 
       let bias_matrix = &bias_mat_array[i];
-      assert_eq!(bias_matrix.rows, 1);
-      assert_eq!(bias_matrix.cols, arch[i + 1]);
+      assert_eq!(bias_matrix.rows(), 1);
+      assert_eq!(bias_matrix.cols(), arch[i + 1]);
+
+      let activation_matrix = &activations_mat_array[i + 1];
+      assert_eq!(activation_matrix.rows(), 1);
+      assert_eq!(activation_matrix.cols(), arch[i + 1]);
+    }
+    assert_eq!(activations_mat_array[0].cols(), arch[0]);
+  }
+
+  #[test]
+  fn test_backprop_gradient_shapes() {
+    let arch = [2, 3, 1];
+    let mut network = NN::new(&arch);
+    network.rand(-1.0, 1.0);
+    let (inputs, outputs) = xor_data();
+
+    let grads = network.backprop(&inputs, &outputs, &Mse);
+
+    assert_eq!(grads.weights.len(), network.count);
+    assert_eq!(grads.bias.len(), network.count);
+    for i in 0..network.count {
+      assert_eq!(
+        (grads.weights[i].rows(), grads.weights[i].cols()),
+        (arch[i], arch[i + 1])
+      );
+      assert_eq!(
+        (grads.bias[i].rows(), grads.bias[i].cols()),
+        (1, arch[i + 1])
+      );
+    }
+  }
+
+  #[test]
+  fn test_backprop_learns_xor() {
+    let mut network = NN::new(&[2, 4, 1]);
+    init_deterministic(&mut network);
+    let (inputs, outputs) = xor_data();
+
+    for _ in 0..20_000 {
+      let grads = network.backprop(&inputs, &outputs, &Mse);
+      network.apply_gradients(&grads, 1.0);
+    }
+
+    for i in 0..4 {
+      let output = network.forward(&mat_row(&inputs, i)).get(0, 0).unwrap();
+      let expected = outputs.get(i, 0).unwrap();
+      assert!(
+        (output - expected).abs() < 0.1,
+        "Row {} predicted {} but expected {}",
+        i,
+        output,
+        expected
+      );
+    }
+  }
+
+  #[test]
+  fn test_gradient_check_matches_backprop() {
+    let mut network = NN::new(&[2, 3, 2, 1]);
+    network.rand(-1.0, 1.0);
+    let (inputs, outputs) = xor_data();
+
+    let errors = network.gradient_check(&inputs, &outputs, &Mse, 1e-5);
+
+    assert_eq!(errors.len(), network.count);
+    for (layer, error) in errors.iter().enumerate() {
+      assert!(
+        *error < 1e-4,
+        "Layer {} has relative error {}",
+        layer,
+        error
+      );
+    }
+  }
+
+  #[test]
+  fn test_finite_diff_leaves_network_unchanged() {
+    let mut network = NN::new(&[2, 2, 1]);
+    network.rand(-1.0, 1.0);
+    let (inputs, outputs) = xor_data();
+    let before: Vec<f64> = (0..2)
+      .flat_map(|i| (0..2).map(move |j| (i, j)))
+      .map(|(i, j)| network.get_weights()[0].get(i, j).unwrap())
+      .collect();
 
-      let activation_matrix = &activations_mat_array[i];
-      assert_eq!(activation_matrix.rows, 1);
-      assert_eq!(activation_matrix.cols, arch[i + 1]);
+    let grads = network.finite_diff(&inputs, &outputs, &Mse, 1e-5);
+
+    assert_eq!(grads.weights.len(), network.count);
+    let after: Vec<f64> = (0..2)
+      .flat_map(|i| (0..2).map(move |j| (i, j)))
+      .map(|(i, j)| network.get_weights()[0].get(i, j).unwrap())
+      .collect();
+    assert_eq!(before, after);
+  }
+
+  #[test]
+  fn test_cost_of_untrained_network() {
+    // With all weights and biases at zero every output is sigmoid(0) = 0.5
+    let network = NN::new(&[2, 3, 1]);
+    let (inputs, outputs) = xor_data();
+
+    let cost = network.cost(&inputs, &outputs, &Mse);
+
+    assert!(
+      (cost - 0.25).abs() < 1e-12,
+      "Expected cost 0.25, got {}",
+      cost
+    );
+  }
+
+  #[test]
+  fn test_cost_accepts_strided_views() {
+    let mut network = NN::new(&[2, 3, 1]);
+    network.rand(-1.0, 1.0);
+    let (inputs, outputs) = xor_data();
+    let mut data: Vec<f64> = Vec::new();
+    for i in 0..4 {
+      data.push(inputs.get(i, 0).unwrap());
+      data.push(inputs.get(i, 1).unwrap());
+      data.push(outputs.get(i, 0).unwrap());
+    }
+    let strided_inputs = MatView::new(&data, 4, 2, 3);
+    let strided_outputs = MatView::new(&data[2..], 4, 1, 3);
+
+    let expected = network.cost(&inputs, &outputs, &Mse);
+    let cost = network.cost(&strided_inputs, &strided_outputs, &Mse);
+
+    assert_eq!(cost, expected);
+  }
+
+  #[test]
+  #[should_panic(expected = "Inputs and targets must have the same number of rows.")]
+  fn test_cost_rejects_mismatched_rows() {
+    let network = NN::new(&[2, 1]);
+    let _cost = network.cost(&Mat::new(4, 2), &Mat::new(3, 1), &Mse);
+  }
+
+  #[test]
+  fn test_gradient_check_with_other_losses() {
+    let mut network = NN::new(&[2, 3, 1]);
+    network.rand(-1.0, 1.0);
+    let (inputs, outputs) = xor_data();
+
+    for errors in [
+      network.gradient_check(&inputs, &outputs, &BinaryCrossEntropy, 1e-5),
+      network.gradient_check(&inputs, &outputs, &Huber::new(0.1), 1e-5),
+    ] {
+      for (layer, error) in errors.iter().enumerate() {
+        assert!(
+          *error < 1e-4,
+          "Layer {} has relative error {}",
+          layer,
+          error
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_gradient_check_with_mixed_activations() {
+    let mut network = NN::with_activations(
+      &[2, 4, 3, 2],
+      &[Activation::Tanh, Activation::Gelu, Activation::Softmax],
+    );
+    network.rand(-1.0, 1.0);
+    let (inputs, xor) = xor_data();
+    // One-hot encode the XOR output as [not xor, xor]
+    let mut outputs = Mat::new(4, 2);
+    for i in 0..4 {
+      let value = xor.get(i, 0).unwrap();
+      outputs.set(i, 0, 1.0 - value);
+      outputs.set(i, 1, value);
     }
+
+    let errors = network.gradient_check(&inputs, &outputs, &CategoricalCrossEntropy, 1e-5);
+
+    for (layer, error) in errors.iter().enumerate() {
+      assert!(
+        *error < 1e-4,
+        "Layer {} has relative error {}",
+        layer,
+        error
+      );
+    }
+  }
+
+  #[test]
+  fn test_linear_output_fits_regression() {
+    // y = 3x - 2 is out of reach for a sigmoid output but trivial for an identity one
+    let mut network = NN::with_activations(&[1, 1], &[Activation::Identity]);
+    let mut inputs = Mat::new(5, 1);
+    let mut outputs = Mat::new(5, 1);
+    for i in 0..5 {
+      let x = i as f64 - 2.0;
+      inputs.set(i, 0, x);
+      outputs.set(i, 0, 3.0 * x - 2.0);
+    }
+
+    for _ in 0..500 {
+      let grads = network.backprop(&inputs, &outputs, &Mse);
+      network.apply_gradients(&grads, 0.1);
+    }
+
+    let cost = network.cost(&inputs, &outputs, &Mse);
+    assert!(cost < 1e-6, "Expected a near perfect fit, cost is {}", cost);
+  }
+
+  #[test]
+  #[should_panic(expected = "Expected one activation per layer. Got 1 activations for 2 layers")]
+  fn test_with_activations_count_mismatch() {
+    let _network = NN::with_activations(&[2, 3, 1], &[Activation::Relu]);
+  }
+
+  #[test]
+  fn test_layer_accessors() {
+    let mut network = NN::with_activations(&[2, 3, 1], &[Activation::Relu, Activation::Identity]);
+    network.weights_mut(1).fill(2);
+    network.bias_mut(1).fill(0.5);
+    network.input_mut().fill(1);
+    let input = network.input().clone();
+    network.forward(&input);
+
+    let layer = network.layer(1);
+    assert_eq!((layer.weights.rows(), layer.weights.cols()), (3, 1));
+    assert_eq!(layer.weights.get(2, 0), Some(2.0));
+    assert_eq!(layer.bias.get(0, 0), Some(0.5));
+    assert_eq!(layer.activation, Activation::Identity);
+    // The hidden weights are all zero, so only the output bias is left
+    assert_eq!(layer.output.get(0, 0), Some(0.5));
+    assert_eq!(network.output().get(0, 0), Some(0.5));
+    assert_eq!(network.input().get(0, 1), Some(1.0));
+  }
+
+  #[test]
+  fn test_input_layer_is_sized_by_the_first_layer() {
+    // Different widths for every layer, so a buffer sized by the wrong layer shows up
+    let mut network = NN::new(&[4, 2, 3]);
+    assert_eq!(network.get_activations().len(), network.count + 1);
+    assert_eq!((network.input().rows(), network.input().cols()), (1, 4));
+    assert_eq!((network.output().rows(), network.output().cols()), (1, 3));
+
+    let output = network.forward(&Mat::from_vec(1, 4, vec![1.0, 2.0, 3.0, 4.0]));
+    assert_eq!((output.rows(), output.cols()), (1, 3));
+    assert_eq!(network.input().get(0, 3), Some(4.0));
+  }
+
+  #[test]
+  fn test_forward_takes_its_input() {
+    let mut network = NN::with_activations(&[3, 2], &[Activation::Identity]);
+    network.weights_mut(0).fill(1);
+    let input = Mat::from_vec(1, 3, vec![1.0, 2.0, 3.0]);
+    assert_eq!(
+      network.forward(&input),
+      &Mat::from_vec(1, 2, vec![6.0, 6.0])
+    );
+    assert_eq!(network.input(), &input);
+    assert_eq!(network.get_activations()[0], input);
+  }
+
+  #[test]
+  fn test_try_forward_rejects_bad_input() {
+    let mut network = NN::new(&[2, 3, 1]);
+    assert_eq!(
+      network.try_forward(&Mat::new(1, 3)).unwrap_err(),
+      NnError::InputWidthMismatch {
+        inputs: (1, 3),
+        expected: 2
+      }
+    );
+    assert_eq!(
+      network.try_forward(&Mat::new(2, 2)).unwrap_err(),
+      NnError::Mat(MatError::ShapeMismatch {
+        operation: "Forward",
+        left: (2, 2),
+        right: (1, 2)
+      })
+    );
+    assert!(network.try_forward(&Mat::new(1, 2)).is_ok());
+  }
+
+  #[test]
+  fn test_try_cost_rejects_bad_dataset() {
+    let mut network = NN::new(&[2, 3, 1]);
+
+    assert_eq!(
+      network.try_cost(&Mat::new(4, 2), &Mat::new(3, 1), &Mse),
+      Err(NnError::SampleCountMismatch {
+        inputs: (4, 2),
+        targets: (3, 1)
+      })
+    );
+    assert_eq!(
+      network.try_cost(&Mat::new(4, 3), &Mat::new(4, 1), &Mse),
+      Err(NnError::InputWidthMismatch {
+        inputs: (4, 3),
+        expected: 2
+      })
+    );
+    assert!(matches!(
+      network.try_backprop(&Mat::new(4, 2), &Mat::new(4, 2), &Mse),
+      Err(NnError::OutputWidthMismatch { expected: 1, .. })
+    ));
+    assert!(network
+      .try_cost(&Mat::new(4, 2), &Mat::new(4, 1), &Mse)
+      .is_ok());
+    assert_eq!(
+      network.try_check_dataset(&Mat::new(4, 2), &Mat::new(4, 3)),
+      Err(NnError::OutputWidthMismatch {
+        targets: (4, 3),
+        expected: 1
+      })
+    );
+    assert_eq!(
+      network.try_check_dataset(&Mat::new(4, 2), &Mat::new(4, 1)),
+      Ok(())
+    );
+  }
+
+  #[test]
+  fn test_f32_network_learns_xor() {
+    let mut network = xor_network::<f32>();
+    let mut inputs = Mat::<f32>::zeros(4, 2);
+    let mut outputs = Mat::<f32>::zeros(4, 1);
+    for i in 0..4 {
+      let (a, b) = (i / 2, i % 2);
+      inputs[(i, 0)] = a as f32;
+      inputs[(i, 1)] = b as f32;
+      outputs[(i, 0)] = (a ^ b) as f32;
+    }
+
+    for _ in 0..5_000 {
+      let grads = network.backprop(&inputs, &outputs, &Mse);
+      network.apply_gradients(&grads, 0.2);
+    }
+
+    let cost: f32 = network.cost(&inputs, &outputs, &Mse);
+    assert!(
+      cost < 1e-2,
+      "f32 network did not learn XOR, cost is {}",
+      cost
+    );
+  }
+
+  #[test]
+  fn test_f32_and_f64_costs_agree() {
+    let mut network = NN::new(&[2, 3, 1]);
+    let mut network_f32 = NN::<f32>::from_architecture(&[2, 3, 1], &[Activation::Sigmoid; 2]);
+    init_deterministic(&mut network);
+    init_deterministic(&mut network_f32);
+    let (inputs, outputs) = xor_data();
+    let inputs_f32 = Mat::from_vec(4, 2, vec![0.0f32, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0]);
+    let outputs_f32 = Mat::from_vec(4, 1, vec![0.0f32, 1.0, 1.0, 0.0]);
+
+    let cost = network.cost(&inputs, &outputs, &Mse);
+    let cost_f32 = network_f32.cost(&inputs_f32, &outputs_f32, &Mse);
+
+    assert!(
+      (cost - cost_f32 as f64).abs() < 1e-6,
+      "f64 cost {} and f32 cost {} differ",
+      cost,
+      cost_f32
+    );
+  }
+
+  #[test]
+  fn test_predict_matches_forward() {
+    let mut network = NN::with_activations(
+      &[3, 5, 4, 2],
+      &[Activation::Relu, Activation::Tanh, Activation::Sigmoid],
+    );
+    network.rand(-1.0, 1.0);
+    let mut inputs = Mat::new(7, 3);
+    inputs.rand(-2.0, 2.0);
+
+    let outputs = network.predict(&inputs);
+    assert_eq!((outputs.rows(), outputs.cols()), (7, 2));
+    for i in 0..7 {
+      let output = network.forward(&mat_row(&inputs, i));
+      for j in 0..2 {
+        assert!((outputs[(i, j)] - output[(0, j)]).abs() < 1e-12);
+      }
+    }
+  }
+
+  #[test]
+  fn test_predict_xor_batch() {
+    let mut network = NN::new(&[2, 4, 1]);
+    init_deterministic(&mut network);
+    let (inputs, outputs) = xor_data();
+    for _ in 0..20_000 {
+      let grads = network.backprop(&inputs, &outputs, &Mse);
+      network.apply_gradients(&grads, 1.0);
+    }
+
+    // A strided view of the inputs works like the matrix itself
+    let mut data: Vec<f64> = Vec::new();
+    for i in 0..4 {
+      data.extend([inputs[(i, 0)], inputs[(i, 1)], outputs[(i, 0)]]);
+    }
+    let predictions = network.predict(&MatView::new(&data, 4, 2, 3));
+    assert_eq!(predictions, network.predict(&inputs));
+    for i in 0..4 {
+      assert!((predictions[(i, 0)] - outputs[(i, 0)]).abs() < 0.1);
+    }
+  }
+
+  #[test]
+  fn test_predict_rejects_wrong_width() {
+    let network = NN::new(&[2, 3, 1]);
+    assert_eq!(
+      network.try_predict(&Mat::new(5, 3)),
+      Err(NnError::InputWidthMismatch {
+        inputs: (5, 3),
+        expected: 2
+      })
+    );
+    assert!(network.try_predict(&Mat::new(5, 2)).is_ok());
   }
 }
@@ -0,0 +1,170 @@
+#[path = "fixtures.rs"]
+mod fixtures;
+#[cfg(test)]
+mod tests {
+  use super::fixtures::fixtures::{xor_data, xor_network};
+  use nn::loss::loss::Mse;
+  use nn::matrix::Mat;
+  use nn::optimizer::optimizer::{
+    Adagrad, Adam, AdamW, Momentum, Nesterov, Optimizer, RmsProp, Sgd,
+  };
+  use nn::trainer::trainer::Trainer;
+
+  fn assert_close(actual: f64, expected: f64) {
+    assert!(
+      (actual - expected).abs() < 1e-9,
+      "Expected {}, got {}",
+      expected,
+      actual
+    );
+  }
+
+  // Runs `steps` updates of a single 1x1 parameter starting at 1 with a constant gradient of 2
+  // and returns the parameter after every step
+  fn trace(optimizer: &mut dyn Optimizer, steps: usize) -> Vec<f64> {
+    let mut param: Mat = Mat::from_vec(1, 1, vec![1.0]);
+    let grad: Mat = Mat::from_vec(1, 1, vec![2.0]);
+    let mut values = Vec::with_capacity(steps);
+    for _ in 0..steps {
+      optimizer.begin_step();
+      optimizer.update(0, &mut param, &grad);
+      values.push(param[(0, 0)]);
+    }
+    return values;
+  }
+
+  #[test]
+  fn test_sgd_matches_apply_gradients() {
+    let (inputs, outputs) = xor_data();
+    let mut stepped = xor_network();
+    let mut applied = xor_network();
+    let grads = stepped.backprop(&inputs, &outputs, &Mse);
+
+    Sgd::new(0.5).step(&mut stepped, &grads);
+    applied.apply_gradients(&grads, 0.5);
+    assert_eq!(stepped.get_weights(), applied.get_weights());
+    assert_eq!(stepped.get_bias(), applied.get_bias());
+  }
+
+  #[test]
+  fn test_momentum_and_nesterov_steps() {
+    let momentum = trace(&mut Momentum::new(0.1, 0.9), 2);
+    assert_close(momentum[0], 0.8);
+    assert_close(momentum[1], 0.42);
+
+    let nesterov = trace(&mut Nesterov::new(0.1, 0.9), 2);
+    assert_close(nesterov[0], 0.62);
+    assert_close(nesterov[1], 0.078);
+  }
+
+  #[test]
+  fn test_adagrad_and_rmsprop_steps() {
+    let adagrad = trace(&mut Adagrad::new(0.1), 2);
+    assert_close(adagrad[0], 0.9);
+    assert_close(adagrad[1], 0.9 - 0.2 / 8.0_f64.sqrt());
+
+    let rmsprop = trace(&mut RmsProp::new(0.01), 1);
+    assert_close(rmsprop[0], 1.0 - 0.02 / 0.4_f64.sqrt());
+  }
+
+  #[test]
+  fn test_adam_first_step_is_learning_rate() {
+    let mut adam = Adam::new(0.01);
+    let mut param: Mat = Mat::from_vec(1, 3, vec![1.0, 1.0, 1.0]);
+    let grad: Mat = Mat::from_vec(1, 3, vec![2.0, -0.5, 300.0]);
+    adam.begin_step();
+    adam.update(0, &mut param, &grad);
+    // Bias correction makes the first step `learning_rate` against the sign of the gradient
+    assert_close(param[(0, 0)], 0.99);
+    assert_close(param[(0, 1)], 1.01);
+    assert_close(param[(0, 2)], 0.99);
+  }
+
+  #[test]
+  fn test_adamw_decays_without_gradient() {
+    let mut adamw = AdamW::new(0.1, 0.5);
+    let mut adam = Adam::new(0.1);
+    let mut decayed: Mat = Mat::from_vec(1, 2, vec![4.0, -2.0]);
+    let mut kept = decayed.clone();
+    let zero: Mat = Mat::new(1, 2);
+    for _ in 0..3 {
+      adamw.begin_step();
+      adamw.update(0, &mut decayed, &zero);
+      adam.begin_step();
+      adam.update(0, &mut kept, &zero);
+    }
+    assert_close(decayed[(0, 0)], 4.0 * 0.95_f64.powi(3));
+    assert_close(decayed[(0, 1)], -2.0 * 0.95_f64.powi(3));
+    assert_eq!(kept, Mat::from_vec(1, 2, vec![4.0, -2.0]));
+  }
+
+  #[test]
+  fn test_every_optimizer_minimizes_a_quadratic() {
+    // f(x) = sum(x^2) / 2 has the gradient x and its minimum at 0
+    let optimizers: Vec<Box<dyn Optimizer>> = vec![
+      Box::new(Sgd::new(0.1)),
+      Box::new(Momentum::new(0.05, 0.9)),
+      Box::new(Nesterov::new(0.05, 0.9)),
+      Box::new(Adagrad::new(0.5)),
+      Box::new(RmsProp::new(0.01)),
+      Box::new(Adam::new(0.05)),
+      Box::new(AdamW::new(0.05, 0.01)),
+    ];
+    for (index, mut optimizer) in optimizers.into_iter().enumerate() {
+      let mut param: Mat = Mat::from_vec(2, 2, vec![3.0, -1.0, 0.5, -4.0]);
+      for _ in 0..1_000 {
+        let grad = param.clone();
+        optimizer.begin_step();
+        optimizer.update(0, &mut param, &grad);
+      }
+      for i in 0..2 {
+        for j in 0..2 {
+          assert!(
+            param[(i, j)].abs() < 0.05,
+            "Optimizer {} ended at {}",
+            index,
+            param[(i, j)]
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_trainer_with_adam_beats_plain_descent() {
+    let (inputs, outputs) = xor_data();
+    let mut trainer = Trainer::new(300, 4, 0.05);
+    trainer.shuffle = false;
+
+    let plain = trainer.train(&mut xor_network(), &inputs, &outputs, &Mse);
+    let mut network = xor_network();
+    let mut adam = Adam::new(0.05);
+    let adaptive = trainer.train_with(&mut network, &inputs, &outputs, &Mse, &mut adam);
+
+    assert!(adaptive[299] < 1e-2, "Final cost is {}", adaptive[299]);
+    assert!(adaptive[299] < plain[299]);
+  }
+
+  #[test]
+  #[should_panic(expected = "Optimizer state of slot 0 is (1x1), but the parameter is (1x2)")]
+  fn test_state_belongs_to_one_parameter() {
+    let mut momentum = Momentum::new(0.1, 0.9);
+    let mut small: Mat = Mat::new(1, 1);
+    let mut wide: Mat = Mat::new(1, 2);
+    momentum.update(0, &mut small, &Mat::new(1, 1));
+    momentum.update(0, &mut wide, &Mat::new(1, 2));
+  }
+
+  #[test]
+  #[should_panic(expected = "Gradient dimensions must match the parameter")]
+  fn test_gradient_shape_mismatch() {
+    let mut param: Mat = Mat::new(2, 2);
+    Sgd::new(0.1).update(0, &mut param, &Mat::new(2, 1));
+  }
+
+  #[test]
+  #[should_panic(expected = "Momentum must be in [0, 1). Got 1")]
+  fn test_momentum_out_of_range() {
+    let _optimizer = Momentum::new(0.1, 1.0);
+  }
+}
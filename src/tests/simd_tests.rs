@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+  use nn::matrix::simd::{self, SimdLevel};
+  use rand::{thread_rng, Rng};
+
+  // Odd lengths leave a tail that does not fill a whole register
+  const LENGTHS: [usize; 6] = [0, 1, 3, 4, 17, 1000];
+
+  type BinaryOp = fn(SimdLevel, &[f64], &[f64], &mut [f64]);
+
+  fn random_values(len: usize) -> Vec<f64> {
+    let mut rng = thread_rng();
+    return (0..len).map(|_| rng.gen_range(-10.0..10.0)).collect();
+  }
+
+  fn assert_bitwise_eq(result: &[f64], expected: &[f64], level: SimdLevel) {
+    for (index, (a, b)) in result.iter().zip(expected).enumerate() {
+      assert_eq!(
+        a.to_bits(),
+        b.to_bits(),
+        "{:?} differs at {}: {} != {}",
+        level,
+        index,
+        a,
+        b
+      );
+    }
+  }
+
+  // A sum of n terms reordered can move by at most about n ulps of the sum of magnitudes
+  fn assert_reduction_close(result: f64, expected: f64, magnitude: f64, n: usize) {
+    let bound = (n.max(1) as f64) * f64::EPSILON * magnitude;
+    assert!(
+      (result - expected).abs() <= bound,
+      "{} and {} differ by more than {}",
+      result,
+      expected,
+      bound
+    );
+  }
+
+  #[test]
+  fn test_detected_level_is_supported() {
+    let levels = simd::supported_levels();
+    assert_eq!(levels.first(), Some(&SimdLevel::Scalar));
+    assert_eq!(levels.last(), Some(&simd::detect()));
+  }
+
+  #[test]
+  fn test_binary_ops_match_scalar_bitwise() {
+    let ops: [BinaryOp; 4] = [
+      simd::add_with,
+      simd::sub_with,
+      simd::mul_with,
+      simd::div_with,
+    ];
+    for len in LENGTHS {
+      let (a, b) = (random_values(len), random_values(len));
+      for op in ops {
+        let mut expected = vec![0.0; len];
+        op(SimdLevel::Scalar, &a, &b, &mut expected);
+        for level in simd::supported_levels() {
+          let mut result = vec![0.0; len];
+          op(level, &a, &b, &mut result);
+          assert_bitwise_eq(&result, &expected, level);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_fill_and_relu_match_scalar_bitwise() {
+    for len in LENGTHS {
+      let values = random_values(len);
+      let mut expected = values.clone();
+      simd::relu_with(SimdLevel::Scalar, &mut expected);
+      for level in simd::supported_levels() {
+        let mut result = values.clone();
+        simd::relu_with(level, &mut result);
+        assert_bitwise_eq(&result, &expected, level);
+
+        simd::fill_with(level, &mut result, 2.5);
+        assert!(result.iter().all(|value| *value == 2.5));
+      }
+    }
+  }
+
+  #[test]
+  fn test_relu_maps_nan_to_zero() {
+    for level in simd::supported_levels() {
+      let mut values = vec![f64::NAN; 5];
+      simd::relu_with(level, &mut values);
+      assert_eq!(values, vec![0.0; 5]);
+    }
+  }
+
+  #[test]
+  fn test_reductions_match_scalar_within_ulps() {
+    for len in LENGTHS {
+      let (a, b) = (random_values(len), random_values(len));
+      let magnitude: f64 = a.iter().map(|x| x.abs()).sum();
+      let product_magnitude: f64 = a.iter().zip(&b).map(|(x, y)| (x * y).abs()).sum();
+      let expected_sum = simd::sum_with(SimdLevel::Scalar, &a);
+      let expected_dot = simd::dot_with(SimdLevel::Scalar, &a, &b);
+      for level in simd::supported_levels() {
+        assert_reduction_close(simd::sum_with(level, &a), expected_sum, magnitude, len);
+        assert_reduction_close(
+          simd::dot_with(level, &a, &b),
+          expected_dot,
+          product_magnitude,
+          len,
+        );
+      }
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "Slice lengths must match. Got a: 3, b: 2 and out: 3")]
+  fn test_length_mismatch() {
+    simd::add(&[1.0, 2.0, 3.0], &[1.0, 2.0], &mut [0.0; 3]);
+  }
+
+  #[test]
+  #[should_panic(expected = "Slice lengths must match. Got a: 3 and b: 2")]
+  fn test_dot_length_mismatch() {
+    simd::dot(&[1.0, 2.0, 3.0], &[1.0, 2.0]);
+  }
+}
@@ -0,0 +1,112 @@
+#[path = "fixtures.rs"]
+mod fixtures;
+#[cfg(test)]
+mod tests {
+  use super::fixtures::fixtures::{xor_data, xor_network};
+  use nn::loss::loss::Mse;
+  use nn::matrix::{Mat, MatView};
+  use nn::network::network::NnError;
+  use nn::trainer::trainer::Trainer;
+
+  #[test]
+  fn test_learns_xor_with_mini_batches() {
+    let (inputs, outputs) = xor_data();
+    let mut network = xor_network();
+    let mut trainer = Trainer::new(2_000, 2, 0.2);
+    trainer.seed = Some(7);
+
+    let history = trainer.train(&mut network, &inputs, &outputs, &Mse);
+
+    assert_eq!(history.len(), 2_000);
+    assert!(history[1_999] < 1e-2, "Final cost is {}", history[1_999]);
+    assert!(history[1_999] < history[0]);
+    assert_eq!(history[1_999], network.cost(&inputs, &outputs, &Mse));
+  }
+
+  #[test]
+  fn test_seed_makes_runs_reproducible() {
+    let (inputs, outputs) = xor_data();
+    let mut trainer = Trainer::new(50, 1, 0.1);
+    trainer.seed = Some(42);
+
+    let first = trainer.train(&mut xor_network(), &inputs, &outputs, &Mse);
+    let second = trainer.train(&mut xor_network(), &inputs, &outputs, &Mse);
+    assert_eq!(first, second);
+
+    trainer.seed = Some(43);
+    let other = trainer.train(&mut xor_network(), &inputs, &outputs, &Mse);
+    assert_ne!(first, other);
+  }
+
+  #[test]
+  fn test_full_batch_matches_plain_gradient_descent() {
+    let (inputs, outputs) = xor_data();
+    let mut trainer = Trainer::new(20, 100, 0.5);
+    trainer.shuffle = false;
+    let mut trained = xor_network();
+    let history = trainer.train(&mut trained, &inputs, &outputs, &Mse);
+
+    let mut manual = xor_network();
+    for &cost in &history {
+      let grads = manual.backprop(&inputs, &outputs, &Mse);
+      manual.apply_gradients(&grads, 0.5);
+      assert_eq!(cost, manual.cost(&inputs, &outputs, &Mse));
+    }
+    assert_eq!(trained.get_weights(), manual.get_weights());
+  }
+
+  #[test]
+  fn test_trains_on_strided_views() {
+    let data = vec![
+      0.0, 0.0, 0.0, //
+      0.0, 1.0, 1.0, //
+      1.0, 0.0, 1.0, //
+      1.0, 1.0, 0.0,
+    ];
+    let inputs = MatView::new(&data, 4, 2, 3);
+    let outputs = MatView::new(&data[2..], 4, 1, 3);
+    let (plain_inputs, plain_outputs) = xor_data();
+    let mut trainer = Trainer::new(10, 3, 0.3);
+    trainer.seed = Some(1);
+
+    let strided = trainer.train(&mut xor_network(), &inputs, &outputs, &Mse);
+    let plain = trainer.train(&mut xor_network(), &plain_inputs, &plain_outputs, &Mse);
+    assert_eq!(strided, plain);
+  }
+
+  #[test]
+  fn test_try_train_rejects_bad_dataset() {
+    let mut network = xor_network();
+    let before = network.get_weights().to_vec();
+    let trainer = Trainer::new(5, 2, 0.1);
+
+    let result = trainer.try_train(&mut network, &Mat::new(4, 2), &Mat::new(3, 1), &Mse);
+    assert_eq!(
+      result,
+      Err(NnError::SampleCountMismatch {
+        inputs: (4, 2),
+        targets: (3, 1)
+      })
+    );
+    assert_eq!(network.get_weights(), &before[..]);
+  }
+
+  #[test]
+  #[should_panic(expected = "Batch size must be greater than 0")]
+  fn test_zero_batch_size() {
+    let _trainer = Trainer::new(1, 0, 0.1);
+  }
+
+  #[test]
+  fn test_try_train_rejects_batch_size_set_to_zero() {
+    let (inputs, outputs) = xor_data();
+    let mut network = xor_network();
+    let before = network.get_weights().to_vec();
+    let mut trainer = Trainer::new(5, 2, 0.1);
+    trainer.batch_size = 0;
+
+    let result = trainer.try_train(&mut network, &inputs, &outputs, &Mse);
+    assert_eq!(result, Err(NnError::ZeroBatchSize));
+    assert_eq!(network.get_weights(), &before[..]);
+  }
+}
@@ -0,0 +1,129 @@
+pub mod trainer {
+  use crate::loss::loss::Loss;
+  use crate::matrix::{mat_copy, mat_row, AsView, AsViewMut, Element, Mat, MatView};
+  use crate::network::network::{Network, NnError};
+  use crate::optimizer::optimizer::{Optimizer, Sgd};
+  use rand::rngs::StdRng;
+  use rand::seq::SliceRandom;
+  use rand::SeedableRng;
+
+  // Mini-batch gradient descent. Every epoch visits each row of the dataset once, in a new
+  // random order unless `shuffle` is off, and takes one step per batch of `batch_size` rows.
+  #[derive(Clone, Debug)]
+  pub struct Trainer<T = f64> {
+    pub epochs: usize,
+    // The last batch of an epoch holds whatever rows are left, so it may be smaller
+    pub batch_size: usize,
+    // Step size of the plain gradient descent in `train`. `train_with` leaves it to the optimizer.
+    pub learning_rate: T,
+    pub shuffle: bool,
+    // Fixes the shuffling order for reproducible runs; `None` draws a fresh seed every time
+    pub seed: Option<u64>,
+  }
+
+  impl<T: Element> Trainer<T> {
+    pub fn new(epochs: usize, batch_size: usize, learning_rate: T) -> Trainer<T> {
+      assert!(batch_size > 0, "Batch size must be greater than 0");
+      return Trainer {
+        epochs,
+        batch_size,
+        learning_rate,
+        shuffle: true,
+        seed: None,
+      };
+    }
+
+    // Trains `network` on `inputs`/`targets` and returns the mean `loss` over the whole
+    // dataset after every epoch, so `history[epochs - 1]` is the final cost
+    pub fn train(
+      &self,
+      network: &mut Network<T>,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+    ) -> Vec<T> {
+      return or_panic(self.try_train(network, inputs, targets, loss));
+    }
+
+    // Like `train`, but rejects a dataset that does not fit the network or a `batch_size` of 0
+    // instead of panicking
+    pub fn try_train(
+      &self,
+      network: &mut Network<T>,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+    ) -> Result<Vec<T>, NnError> {
+      let mut optimizer = Sgd::new(self.learning_rate);
+      return self.try_train_with(network, inputs, targets, loss, &mut optimizer);
+    }
+
+    // Like `train`, but every batch's gradients go through `optimizer`, e.g. `Adam`. The
+    // optimizer keeps its state between calls, so a second call continues the same run.
+    pub fn train_with(
+      &self,
+      network: &mut Network<T>,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+      optimizer: &mut dyn Optimizer<T>,
+    ) -> Vec<T> {
+      return or_panic(self.try_train_with(network, inputs, targets, loss, optimizer));
+    }
+
+    pub fn try_train_with(
+      &self,
+      network: &mut Network<T>,
+      inputs: &impl AsView<T>,
+      targets: &impl AsView<T>,
+      loss: &dyn Loss<T>,
+      optimizer: &mut dyn Optimizer<T>,
+    ) -> Result<Vec<T>, NnError> {
+      if self.batch_size == 0 {
+        return Err(NnError::ZeroBatchSize);
+      }
+      let (inputs, targets) = (inputs.view(), targets.view());
+      network.try_check_dataset(&inputs, &targets)?;
+
+      let mut rng = match self.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+      };
+      let mut order: Vec<usize> = (0..inputs.rows()).collect();
+      let mut history = Vec::with_capacity(self.epochs);
+      for _ in 0..self.epochs {
+        if self.shuffle {
+          order.shuffle(&mut rng);
+        }
+        for batch in order.chunks(self.batch_size) {
+          let batch_inputs = gather_rows(inputs, batch);
+          let batch_targets = gather_rows(targets, batch);
+          let grads = network.backprop(&batch_inputs, &batch_targets, loss);
+          optimizer.step(network, &grads);
+        }
+        history.push(network.cost(&inputs, &targets, loss));
+      }
+      return Ok(history);
+    }
+  }
+
+  // Unwraps the result of a `try_*` method, panicking with the error message
+  fn or_panic<R>(result: Result<R, NnError>) -> R {
+    return match result {
+      Ok(value) => value,
+      Err(error) => panic!("{}", error),
+    };
+  }
+
+  // The rows of `mat` listed in `rows`, in that order
+  fn gather_rows<T: Element>(mat: MatView<T>, rows: &[usize]) -> Mat<T> {
+    let mut result = Mat::zeros(rows.len(), mat.cols());
+    for (i, &row) in rows.iter().enumerate() {
+      mat_copy(
+        &mut result.view_mut().slice_mut(i..=i, ..),
+        &mat_row(&mat, row),
+      );
+    }
+    return result;
+  }
+}
@@ -1,3 +1,10 @@
-pub fn sigmoid(x: f64) -> f64 {
-  return 1.0 / (1.0 + (-x).exp());
+use num_traits::Float;
+
+pub fn sigmoid<T: Float>(x: T) -> T {
+  return T::one() / (T::one() + (-x).exp());
+}
+
+// Derivative of the sigmoid expressed through its output `a = sigmoid(x)`
+pub fn sigmoid_derivative<T: Float>(a: T) -> T {
+  return a * (T::one() - a);
 }